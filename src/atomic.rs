@@ -0,0 +1,123 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{size_of, MaybeUninit};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::NoUninit;
+
+/// A read-mostly value of a small `Copy` type, stored in an atomic.
+///
+/// `RoAtomic` is for values no larger than a pointer, such as enums, flags and pointers, that
+/// need to be changed at runtime, e.g. log levels and feature switches. Note that this rules out
+/// `u64` and `f64` on 32-bit targets. Unlike
+/// [`RoCell::replace`], [`RoAtomic::set`] is safe and may race with readers.
+///
+/// [`RoAtomic::get`] is a relaxed load, which on mainstream targets compiles to the same
+/// instruction as reading a `RoCell`.
+///
+/// [`RoCell::replace`]: crate::RoCell::replace
+pub struct RoAtomic<T> {
+    repr: AtomicPtr<()>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// Like `AtomicPtr`, and unlike `PhantomData<T>`, this does not depend on `T: Send + Sync`, so
+// `RoAtomic` works with raw pointers. `NoUninit` requires values to be safe to share otherwise.
+unsafe impl<T: NoUninit> Send for RoAtomic<T> {}
+unsafe impl<T: NoUninit> Sync for RoAtomic<T> {}
+
+impl<T: NoUninit> RoAtomic<T> {
+    /// Create a new `RoAtomic`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is larger than a pointer.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        assert!(size_of::<T>() <= size_of::<*mut ()>());
+        RoAtomic {
+            repr: AtomicPtr::new(Self::to_repr(value)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the value with a relaxed load.
+    #[inline]
+    pub fn get(&self) -> T {
+        self.load(Ordering::Relaxed)
+    }
+
+    /// Get the value with the given memory ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`](Ordering::Release) or [`AcqRel`](Ordering::AcqRel).
+    #[inline]
+    pub fn load(&self, order: Ordering) -> T {
+        Self::from_repr(self.repr.load(order))
+    }
+
+    /// Set the value with a release store.
+    #[inline]
+    pub fn set(&self, value: T) {
+        self.store(value, Ordering::Release);
+    }
+
+    /// Set the value with the given memory ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Acquire`](Ordering::Acquire) or [`AcqRel`](Ordering::AcqRel).
+    #[inline]
+    pub fn store(&self, value: T, order: Ordering) {
+        self.repr.store(Self::to_repr(value), order);
+    }
+
+    /// Set the value with the given memory ordering, and return the old value.
+    ///
+    /// Unlike [`RoAtomic::load`] and [`RoAtomic::store`], this accepts all orderings.
+    #[inline]
+    pub fn swap(&self, value: T, order: Ordering) -> T {
+        Self::from_repr(self.repr.swap(Self::to_repr(value), order))
+    }
+
+    /// Consume the `RoAtomic` and return its value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        Self::from_repr(self.repr.into_inner())
+    }
+
+    #[inline]
+    const fn to_repr(value: T) -> *mut () {
+        // Copy bytewise so that pointers keep their provenance. `T` has no uninitialized bytes,
+        // and the remaining bytes are zero.
+        let mut repr = MaybeUninit::<*mut ()>::zeroed();
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                &value as *const T as *const u8,
+                repr.as_mut_ptr() as *mut u8,
+                size_of::<T>(),
+            );
+            repr.assume_init()
+        }
+    }
+
+    #[inline]
+    fn from_repr(repr: *mut ()) -> T {
+        let mut value = MaybeUninit::<T>::uninit();
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                &repr as *const *mut () as *const u8,
+                value.as_mut_ptr() as *mut u8,
+                size_of::<T>(),
+            );
+            value.assume_init()
+        }
+    }
+}
+
+impl<T: NoUninit + fmt::Debug> fmt::Debug for RoAtomic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
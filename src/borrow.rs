@@ -0,0 +1,92 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::RoCell;
+
+/// A guard for a shared borrow of the content of a [`RoCell`].
+///
+/// Created by [`RoCell::borrow`]. In checked mode, the `RoCell` cannot be mutated while this guard
+/// is alive; attempts to do so panic.
+pub struct Ref<'a, T> {
+    cell: &'a RoCell<T>,
+}
+
+impl<'a, T> Ref<'a, T> {
+    #[inline]
+    #[track_caller]
+    pub(crate) fn new(cell: &'a RoCell<T>) -> Self {
+        cell.1.assert_init();
+        cell.1.acquire_shared();
+        Ref { cell }
+    }
+}
+
+impl<T> Drop for Ref<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.1.release_shared();
+    }
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*RoCell::as_ptr(self.cell) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.deref(), f)
+    }
+}
+
+/// A guard for an exclusive borrow of the content of a [`RoCell`].
+///
+/// Created by [`RoCell::borrow_mut`]. In checked mode, the `RoCell` cannot be read through
+/// `Deref`, borrowed or mutated while this guard is alive; attempts to do so panic.
+pub struct RefMut<'a, T> {
+    cell: &'a RoCell<T>,
+}
+
+impl<'a, T> RefMut<'a, T> {
+    #[inline]
+    #[track_caller]
+    pub(crate) fn new(cell: &'a RoCell<T>) -> Self {
+        crate::seal::assert_unsealed();
+        cell.1.assert_init();
+        cell.1.acquire_exclusive();
+        RefMut { cell }
+    }
+}
+
+impl<T> Drop for RefMut<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.1.release_exclusive();
+    }
+}
+
+impl<T> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*RoCell::as_ptr(self.cell) }
+    }
+}
+
+impl<T> DerefMut for RefMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *RoCell::as_mut_ptr(self.cell) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.deref(), f)
+    }
+}
@@ -0,0 +1,36 @@
+/// Register a block of code to run before `main`.
+///
+/// Blocks registered as `early` run before all others, except on Apple targets, where
+/// constructors have no priorities.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ro_ctor {
+    (early $body:block) => {
+        $crate::__ro_ctor!(".init_array.00101", ".CRT$XCT", $body);
+    };
+    ($body:block) => {
+        $crate::__ro_ctor!(".init_array", ".CRT$XCU", $body);
+    };
+    ($elf:literal, $windows:literal, $body:block) => {
+        const _: () = {
+            #[used]
+            #[cfg_attr(
+                any(
+                    target_os = "linux",
+                    target_os = "android",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd",
+                    target_os = "dragonfly",
+                    target_os = "illumos",
+                ),
+                link_section = $elf
+            )]
+            #[cfg_attr(target_vendor = "apple", link_section = "__DATA,__mod_init_func")]
+            #[cfg_attr(windows, link_section = $windows)]
+            static CTOR: extern "C" fn() = ctor;
+
+            extern "C" fn ctor() $body
+        };
+    };
+}
@@ -0,0 +1,132 @@
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{seal, RoCell};
+
+const UNSET: u8 = 0;
+const SETTING: u8 = 1;
+const SET: u8 = 2;
+
+/// A set-once global slot for a `&'static` reference, typically to a trait object.
+///
+/// This is the pattern used by `log::set_logger`: the slot starts out pointing to a default
+/// object, usually a no-op implementation, until [`RoDyn::set`] installs the real one. Unlike
+/// rolling it by hand on top of [`RoCell::new_uninit`], `RoDyn` is entirely safe to use.
+///
+/// Reading costs an atomic load and a branch on top of reading a `RoCell`.
+///
+/// ```
+/// use ro_cell::RoDyn;
+///
+/// trait Tracer: Sync {
+///     fn trace(&self, msg: &str);
+/// }
+///
+/// struct NopTracer;
+/// impl Tracer for NopTracer {
+///     fn trace(&self, _: &str) {}
+/// }
+///
+/// static TRACER: RoDyn<dyn Tracer> = RoDyn::new(&NopTracer);
+///
+/// TRACER.get().trace("dropped");
+/// ```
+pub struct RoDyn<T: ?Sized + 'static> {
+    state: AtomicU8,
+    default: &'static T,
+    value: RoCell<&'static T>,
+}
+
+/// The error returned by [`RoDyn::try_set`] if the `RoDyn` is already set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetError(());
+
+impl fmt::Display for SetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("RoDyn is already set")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SetError {}
+
+impl<T: ?Sized + 'static> RoDyn<T> {
+    /// Create a new `RoDyn` that refers to `default` until it is set.
+    #[cfg(not(loom))]
+    #[inline]
+    pub const fn new(default: &'static T) -> Self {
+        RoDyn {
+            state: AtomicU8::new(UNSET),
+            default,
+            value: unsafe { RoCell::new_uninit() },
+        }
+    }
+
+    /// Create a new `RoDyn` that refers to `default` until it is set.
+    #[cfg(loom)]
+    pub fn new(default: &'static T) -> Self {
+        RoDyn {
+            state: AtomicU8::new(UNSET),
+            default,
+            value: unsafe { RoCell::new_uninit() },
+        }
+    }
+
+    /// Get the current reference.
+    #[inline]
+    pub fn get(&self) -> &'static T {
+        if self.state.load(Ordering::Acquire) == SET {
+            *self.value
+        } else {
+            self.default
+        }
+    }
+
+    /// Set the reference, if it has not been set yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`](crate::seal) has been called.
+    #[track_caller]
+    pub fn try_set(&self, value: &'static T) -> Result<(), SetError> {
+        // Check before claiming the slot, so a panic does not leave it stuck in `SETTING`.
+        seal::assert_unsealed();
+        if self
+            .state
+            .compare_exchange(UNSET, SETTING, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(SetError(()));
+        }
+        // The `SETTING` state gives us exclusive access, and readers do not access
+        // `value` until they observe `SET`. The seal is not checked again, as `seal` may have
+        // been called since, and panicking now would leave the slot stuck in `SETTING`.
+        unsafe { RoCell::init_unsealed(&self.value, value) };
+        self.state.store(SET, Ordering::Release);
+        Ok(())
+    }
+
+    /// Set the reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `RoDyn` is already set, or if [`seal`](crate::seal) has been called.
+    #[track_caller]
+    pub fn set(&self, value: &'static T) {
+        if self.try_set(value).is_err() {
+            panic!("RoDyn is already set");
+        }
+    }
+
+    /// Check if the reference has been set.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.state.load(Ordering::Acquire) == SET
+    }
+}
+
+impl<T: ?Sized + fmt::Debug + 'static> fmt::Debug for RoDyn<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.get(), f)
+    }
+}
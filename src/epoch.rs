@@ -0,0 +1,106 @@
+//! Read indicators for waiting out readers, shared by `RoLeftRight` and `RoRcu`.
+//!
+//! Readers register with one of two indicators, chosen by a version index. A writer that has
+//! published a new version toggles the index and waits for both indicators to drain in turn, after
+//! which every reader that could have seen the old version is gone. This is the scheme of
+//! Ramalhete and Correia's Left-Right algorithm.
+//!
+//! Each indicator is split into shards, one per thread as far as there are enough, each on its own
+//! cache line. Readers on different threads therefore do not contend on a shared counter, at the
+//! price of writers scanning all shards.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ReadMostly;
+
+/// Number of reader shards. Must be a power of two.
+const SHARDS: usize = 32;
+
+pub(crate) struct Epoch {
+    index: AtomicUsize,
+    /// Reader counts of each shard, one for each version index.
+    shards: [ReadMostly<[AtomicUsize; 2]>; SHARDS],
+}
+
+/// The counter a reader registered with.
+pub(crate) struct Ticket {
+    shard: usize,
+    index: usize,
+}
+
+impl Epoch {
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Epoch {
+            index: AtomicUsize::new(0),
+            shards: [const { ReadMostly::new([AtomicUsize::new(0), AtomicUsize::new(0)]) }; SHARDS],
+        }
+    }
+
+    /// Register a reader. The returned ticket must be passed to `leave`.
+    #[inline]
+    pub(crate) fn enter(&self) -> Ticket {
+        let shard = shard();
+        let index = self.index.load(Ordering::SeqCst);
+        self.shards[shard][index].fetch_add(1, Ordering::SeqCst);
+        Ticket { shard, index }
+    }
+
+    #[inline]
+    pub(crate) fn leave(&self, ticket: &Ticket) {
+        self.shards[ticket.shard][ticket.index].fetch_sub(1, Ordering::Release);
+    }
+
+    /// Wait for all readers that entered before this call to leave.
+    ///
+    /// Calls must be serialized by the caller.
+    pub(crate) fn synchronize(&self) {
+        let prev = self.index.load(Ordering::Relaxed);
+        let next = prev ^ 1;
+        self.wait(next);
+        self.index.store(next, Ordering::SeqCst);
+        self.wait(prev);
+    }
+
+    fn wait(&self, index: usize) {
+        for shard in &self.shards {
+            while shard[index].load(Ordering::SeqCst) != 0 {
+                relax();
+            }
+        }
+    }
+}
+
+/// Pick the shard for the current thread.
+#[cfg(feature = "std")]
+#[inline]
+fn shard() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    std::thread_local! {
+        static SHARD: usize = NEXT.fetch_add(1, Ordering::Relaxed) % SHARDS;
+    }
+
+    // Thread-locals are gone while the thread is being torn down.
+    SHARD.try_with(|shard| *shard).unwrap_or(0)
+}
+
+/// Pick the shard for the current thread.
+///
+/// Without thread-locals, this hashes the stack address. Threads have disjoint stacks, so they
+/// mostly end up in different shards.
+#[cfg(not(feature = "std"))]
+#[inline]
+fn shard() -> usize {
+    let marker = 0u8;
+    let addr = &marker as *const u8 as usize >> 16;
+    addr.wrapping_mul(0x9E37_79B9_7F4A_7C15_u64 as usize) >> (usize::BITS - SHARDS.trailing_zeros())
+}
+
+#[inline]
+pub(crate) fn relax() {
+    #[cfg(feature = "std")]
+    std::thread::yield_now();
+    #[cfg(not(feature = "std"))]
+    core::hint::spin_loop();
+}
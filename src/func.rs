@@ -0,0 +1,201 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+union Repr<F: Copy> {
+    func: F,
+    ptr: *mut (),
+}
+
+/// A function pointer that can be patched at runtime.
+///
+/// `RoFn` is typically used for multiversioned functions: it starts out pointing to a resolver,
+/// which picks an implementation using runtime CPU feature detection on the first call, and
+/// patches the `RoFn` to point to it, so later calls are a single indirect call. See [`ro_fn!`]
+/// for a macro that generates all of this.
+///
+/// The function pointer is stored in an atomic, so it can be patched while other threads are
+/// calling through it. Reading it compiles to a plain load.
+///
+/// [`ro_fn!`]: crate::ro_fn
+pub struct RoFn<F> {
+    ptr: AtomicPtr<()>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Copy> RoFn<F> {
+    /// Create a new `RoFn` pointing to `func`.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be a function pointer type.
+    #[inline]
+    pub const unsafe fn new(func: F) -> Self {
+        assert!(size_of::<F>() == size_of::<*mut ()>());
+        RoFn {
+            ptr: AtomicPtr::new(Repr { func }.ptr),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the current function pointer.
+    #[inline]
+    pub fn get(&self) -> F {
+        unsafe {
+            Repr {
+                ptr: self.ptr.load(Ordering::Relaxed),
+            }
+            .func
+        }
+    }
+
+    /// Point this `RoFn` to `func`.
+    #[inline]
+    pub fn set(&self, func: F) {
+        self.ptr.store(unsafe { Repr { func }.ptr }, Ordering::Relaxed);
+    }
+
+    /// Point this `RoFn` to `func`, and return the previous function pointer.
+    #[inline]
+    pub fn replace(&self, func: F) -> F {
+        let ptr = self
+            .ptr
+            .swap(unsafe { Repr { func }.ptr }, Ordering::Relaxed);
+        unsafe { Repr { ptr }.func }
+    }
+}
+
+impl<F> fmt::Debug for RoFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.ptr.load(Ordering::Relaxed), f)
+    }
+}
+
+/// Declare a function that dispatches to one of several implementations at runtime.
+///
+/// The first call evaluates the predicates in order and picks the first implementation whose
+/// predicate holds, or the `else` implementation if none does. The choice is stored in a
+/// [`RoFn`], so later calls are a single indirect call. To resolve eagerly, e.g. during startup,
+/// call `resolve` on the function's name, as in `sum::resolve()` below. This picks the
+/// implementation without calling it. `resolve` has the same visibility as the function.
+///
+/// If the selection block is marked `unsafe`, the implementations may be `unsafe` functions, such
+/// as functions with `#[target_feature]`. Each predicate must then guarantee that calling the
+/// implementation it guards is safe.
+///
+/// ```
+/// use ro_cell::ro_fn;
+///
+/// fn sum_scalar(xs: &[u32]) -> u32 {
+///     xs.iter().sum()
+/// }
+///
+/// #[cfg(target_arch = "x86_64")]
+/// #[target_feature(enable = "avx2")]
+/// unsafe fn sum_avx2(xs: &[u32]) -> u32 {
+///     xs.iter().sum()
+/// }
+///
+/// #[cfg(target_arch = "x86_64")]
+/// ro_fn! {
+///     pub fn sum(xs: &[u32]) -> u32 = unsafe {
+///         if is_x86_feature_detected!("avx2") => sum_avx2,
+///         else => sum_scalar,
+///     }
+/// }
+///
+/// #[cfg(not(target_arch = "x86_64"))]
+/// ro_fn! {
+///     pub fn sum(xs: &[u32]) -> u32 = {
+///         else => sum_scalar,
+///     }
+/// }
+///
+/// sum::resolve();
+/// assert_eq!(sum(&[1, 2, 3]), 6);
+/// ```
+#[macro_export]
+macro_rules! ro_fn {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $argty:ty),* $(,)?) $(-> $ret:ty)? = unsafe {
+            $(if $cond:expr => $imp:expr,)*
+            else => $default:expr $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            $vis fn $name($($arg: $argty),*) $(-> $ret)? {
+                unsafe { ($name::dispatch().get())($($arg),*) }
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            $vis struct $name {}
+
+            #[allow(dead_code)]
+            impl $name {
+                /// Pick the implementation now, instead of on the first call.
+                $vis fn resolve() {
+                    Self::dispatch().set(Self::select());
+                }
+
+                // The predicates are user code, so they are evaluated in a safe function. Only
+                // calling the chosen implementation is unsafe.
+                fn select() -> unsafe fn($($argty),*) $(-> $ret)? {
+                    $(if $cond { $imp } else)* { $default }
+                }
+
+                fn dispatch() -> &'static $crate::RoFn<unsafe fn($($argty),*) $(-> $ret)?> {
+                    unsafe fn resolve($($arg: $argty),*) $(-> $ret)? {
+                        $name::resolve();
+                        ($name::dispatch().get())($($arg),*)
+                    }
+
+                    static DISPATCH: $crate::RoFn<unsafe fn($($argty),*) $(-> $ret)?> =
+                        unsafe { $crate::RoFn::new(resolve as unsafe fn($($argty),*) $(-> $ret)?) };
+                    &DISPATCH
+                }
+            }
+        )*
+    };
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $argty:ty),* $(,)?) $(-> $ret:ty)? = {
+            $(if $cond:expr => $imp:expr,)*
+            else => $default:expr $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            $vis fn $name($($arg: $argty),*) $(-> $ret)? {
+                ($name::dispatch().get())($($arg),*)
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            $vis struct $name {}
+
+            #[allow(dead_code)]
+            impl $name {
+                /// Pick the implementation now, instead of on the first call.
+                $vis fn resolve() {
+                    let func: fn($($argty),*) $(-> $ret)? = $(if $cond { $imp } else)* { $default };
+                    Self::dispatch().set(func);
+                }
+
+                fn dispatch() -> &'static $crate::RoFn<fn($($argty),*) $(-> $ret)?> {
+                    fn resolve($($arg: $argty),*) $(-> $ret)? {
+                        $name::resolve();
+                        ($name::dispatch().get())($($arg),*)
+                    }
+
+                    static DISPATCH: $crate::RoFn<fn($($argty),*) $(-> $ret)?> =
+                        unsafe { $crate::RoFn::new(resolve as fn($($argty),*) $(-> $ret)?) };
+                    &DISPATCH
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,130 @@
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::RoFn;
+
+/// A function pointer hook that can be wrapped by other hooks.
+///
+/// Installing a hook saves the previously installed function pointer, so the new hook can chain
+/// to the earlier behaviour, in the style of `LD_PRELOAD` interposition or panic hook chaining.
+/// Hooks can be uninstalled again in reverse order of installation, using the [`HookToken`]
+/// returned by [`RoHook::install`].
+///
+/// Calling through the hook is a plain load plus an indirect call. Installation and removal are
+/// serialized by an internal lock.
+///
+/// ```
+/// use ro_cell::{RoFn, RoHook};
+///
+/// fn base(x: u32) -> u32 {
+///     x
+/// }
+///
+/// static HOOK: RoHook<fn(u32) -> u32> = unsafe { RoHook::new(base) };
+/// static PREV: RoFn<fn(u32) -> u32> = unsafe { RoFn::new(base) };
+///
+/// fn double(x: u32) -> u32 {
+///     PREV.get()(x) * 2
+/// }
+///
+/// let token = HOOK.install(double, &PREV);
+/// assert_eq!(HOOK.get()(3), 6);
+/// assert!(HOOK.uninstall(token, &PREV).is_ok());
+/// assert_eq!(HOOK.get()(3), 3);
+/// ```
+pub struct RoHook<F> {
+    func: RoFn<F>,
+    lock: AtomicBool,
+    /// Id of the topmost installed hook, or 0 if none is installed.
+    top: AtomicUsize,
+}
+
+/// Source of hook ids. Ids are unique across all `RoHook`s, so a token of one hook is never
+/// accepted by another.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// A token identifying a hook installed with [`RoHook::install`], used to uninstall it.
+///
+/// Function pointers have no reliable identity, as identical functions may be merged and one
+/// function may have several addresses, so hooks are identified by the order of installation
+/// instead.
+#[must_use = "the token is needed to uninstall the hook"]
+#[derive(Debug)]
+pub struct HookToken {
+    id: usize,
+    below: usize,
+}
+
+impl<F: Copy> RoHook<F> {
+    /// Create a new `RoHook` pointing to `func`.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be a function pointer type.
+    #[inline]
+    pub const unsafe fn new(func: F) -> Self {
+        RoHook {
+            func: RoFn::new(func),
+            lock: AtomicBool::new(false),
+            top: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the current function pointer.
+    #[inline]
+    pub fn get(&self) -> F {
+        self.func.get()
+    }
+
+    /// Install `hook`, and return a token to uninstall it with.
+    ///
+    /// The previous function pointer is stored in `prev` before `hook` is made visible, so `hook`
+    /// can chain to it through `prev`. As all accesses are relaxed, a thread calling the hook
+    /// concurrently may briefly observe the old content of `prev`; it is always a valid function
+    /// pointer, but may skip part of the chain.
+    pub fn install(&self, hook: F, prev: &RoFn<F>) -> HookToken {
+        self.locked(|| {
+            prev.set(self.func.get());
+            self.func.set(hook);
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let below = self.top.swap(id, Ordering::Relaxed);
+            HookToken { id, below }
+        })
+    }
+
+    /// Uninstall the hook identified by `token`, restoring the function pointer saved in `prev` by
+    /// [`RoHook::install`]. `prev` must be the `RoFn` passed to `install` along with the hook.
+    ///
+    /// Returns the token and does nothing if the hook is not the topmost one, e.g. because another
+    /// hook has been installed on top of it. It can be uninstalled once the hooks above it are.
+    /// A token returned by another `RoHook` is returned as well.
+    pub fn uninstall(&self, token: HookToken, prev: &RoFn<F>) -> Result<(), HookToken> {
+        self.locked(|| {
+            if self.top.load(Ordering::Relaxed) != token.id {
+                return Err(token);
+            }
+            self.func.set(prev.get());
+            self.top.store(token.below, Ordering::Relaxed);
+            Ok(())
+        })
+    }
+
+    fn locked<R>(&self, f: impl FnOnce() -> R) -> R {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let ret = f();
+        self.lock.store(false, Ordering::Release);
+        ret
+    }
+}
+
+impl<F> fmt::Debug for RoHook<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.func, f)
+    }
+}
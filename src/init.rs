@@ -0,0 +1,282 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering};
+
+use crate::RoCell;
+
+const PENDING: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+
+static HEAD: AtomicPtr<Initializer> = AtomicPtr::new(ptr::null_mut());
+
+/// A static declared with [`ro_init!`].
+///
+/// `RoInit` is a `RoCell` with an initialization flag that is checked on every read, in all
+/// builds. Reading it before its initializer has run, e.g. from another initializer that does not
+/// list it in `after`, or from an unrelated static constructor, panics instead of reading
+/// uninitialized memory. Reading costs a load of the flag and a predictable branch on top of
+/// reading a `RoCell`.
+///
+/// [`ro_init!`]: crate::ro_init
+pub struct RoInit<T> {
+    cell: ManuallyDrop<RoCell<T>>,
+    init: AtomicBool,
+}
+
+impl<T> Drop for RoInit<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if *self.init.get_mut() {
+            unsafe { ManuallyDrop::drop(&mut self.cell) };
+        }
+    }
+}
+
+impl<T> RoInit<T> {
+    #[doc(hidden)]
+    #[cfg(not(loom))]
+    #[inline]
+    pub const fn __new() -> Self {
+        RoInit {
+            cell: ManuallyDrop::new(unsafe { RoCell::new_uninit() }),
+            init: AtomicBool::new(false),
+        }
+    }
+
+    #[doc(hidden)]
+    #[cfg(loom)]
+    pub fn __new() -> Self {
+        RoInit {
+            cell: ManuallyDrop::new(unsafe { RoCell::new_uninit() }),
+            init: AtomicBool::new(false),
+        }
+    }
+
+    /// Initialize the static. Called by the initializer generated by [`ro_init!`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::init`].
+    #[doc(hidden)]
+    #[inline]
+    #[track_caller]
+    pub unsafe fn __init(this: &Self, value: T) {
+        RoCell::init(&this.cell, value);
+        this.init.store(true, Ordering::Release);
+    }
+}
+
+impl<T> Deref for RoInit<T> {
+    type Target = T;
+
+    #[inline]
+    #[track_caller]
+    fn deref(&self) -> &T {
+        if !self.init.load(Ordering::Acquire) {
+            panic!("ro_init! static read before its initializer ran");
+        }
+        &self.cell
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RoInit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.deref(), f)
+    }
+}
+
+/// A dependency of an [`Initializer`], identified by the address of its `RoInit`.
+#[doc(hidden)]
+pub struct Dependency(*const ());
+
+unsafe impl Sync for Dependency {}
+
+impl Dependency {
+    pub const fn new<T>(cell: &'static RoInit<T>) -> Self {
+        Dependency(cell as *const RoInit<T> as *const ())
+    }
+}
+
+/// An initializer declared by [`ro_init!`].
+#[doc(hidden)]
+pub struct Initializer {
+    name: &'static str,
+    cell: *const (),
+    deps: &'static [Dependency],
+    init: fn(),
+    state: AtomicU8,
+    next: AtomicPtr<Initializer>,
+}
+
+unsafe impl Sync for Initializer {}
+
+impl Initializer {
+    pub const fn new<T>(
+        name: &'static str,
+        cell: &'static RoInit<T>,
+        deps: &'static [Dependency],
+        init: fn(),
+    ) -> Self {
+        Initializer {
+            name,
+            cell: cell as *const RoInit<T> as *const (),
+            deps,
+            init,
+            state: AtomicU8::new(PENDING),
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Add this initializer to the global list. Called by an early constructor, so that all
+    /// initializers are registered before the first one runs.
+    pub fn register(&'static self) {
+        self.next.store(HEAD.load(Ordering::Relaxed), Ordering::Relaxed);
+        HEAD.store(self as *const Self as *mut Self, Ordering::Relaxed);
+    }
+
+    /// Run all registered initializers that have not run yet, in dependency order.
+    ///
+    /// Every `ro_init!` constructor calls this. Only the first call in the main program does
+    /// anything, but a shared object loaded later with `dlopen` registers new initializers, which
+    /// its own constructors then run.
+    pub fn run_all() {
+        for init in Self::iter() {
+            init.run();
+        }
+    }
+
+    fn iter() -> impl Iterator<Item = &'static Initializer> {
+        let mut next = HEAD.load(Ordering::Relaxed);
+        core::iter::from_fn(move || {
+            let init = unsafe { next.as_ref()? };
+            next = init.next.load(Ordering::Relaxed);
+            Some(init)
+        })
+    }
+
+    fn run(&'static self) {
+        if self.state.load(Ordering::Relaxed) == DONE {
+            return;
+        }
+        self.state.store(RUNNING, Ordering::Relaxed);
+        for dep in self.deps {
+            let dep = match Self::iter().find(|init| init.cell == dep.0) {
+                Some(dep) => dep,
+                None => panic!(
+                    "ro_init! initializer `{}` depends on a static not declared with ro_init!",
+                    self.name
+                ),
+            };
+            if dep.state.load(Ordering::Relaxed) == RUNNING {
+                panic!(
+                    "cyclic dependency between ro_init! initializers `{}` and `{}`",
+                    self.name, dep.name
+                );
+            }
+            dep.run();
+        }
+        (self.init)();
+        self.state.store(DONE, Ordering::Relaxed);
+    }
+}
+
+/// Declare statics that are initialized before `main`.
+///
+/// Each static is a [`RoInit`], created uninitialized, and an initializer that initializes it with
+/// the given expression is registered with the platform's static constructor mechanism
+/// (`.init_array` on ELF targets and `.CRT$XCU` on Windows). The initializers run before `main`
+/// and before any other thread exists, so by the time `main` runs, the statics can be read from
+/// anywhere in the program, with the cost of a plain load and a check of the initialization flag.
+///
+/// An initializer that reads other statics declared with `ro_init!` must list them in an `after`
+/// clause. Initializers run in dependency order, and otherwise in an unspecified order. Reading a
+/// static before its initializer has run, e.g. because it is missing from `after`, or from another
+/// static constructor, panics. So does a cyclic dependency.
+///
+/// Initializers run before the Rust runtime is fully set up, and a panic in an initializer
+/// aborts the process.
+///
+/// This macro is not available on Apple targets, as `__mod_init_func` has no priorities to
+/// register all initializers before the first one runs.
+///
+/// ```
+/// use ro_cell::ro_init;
+///
+/// ro_init! {
+///     static ANSWER: RoInit<u32> = 6 * 7;
+///     static DOUBLE: RoInit<u32> = *ANSWER * 2; after ANSWER;
+/// }
+///
+/// fn main() {
+///     assert_eq!(*ANSWER, 42);
+///     assert_eq!(*DOUBLE, 84);
+/// }
+/// ```
+///
+/// Only statics declared with `ro_init!` can be dependencies:
+///
+/// ```compile_fail
+/// use ro_cell::{ro_init, RoCell};
+///
+/// static PLAIN: RoCell<u32> = RoCell::new(1);
+///
+/// ro_init! {
+///     static DOUBLE: RoInit<u32> = *PLAIN * 2; after PLAIN;
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! ro_init {
+    () => {};
+    (
+        $(#[$attr:meta])* $vis:vis static $name:ident: RoInit<$ty:ty> = $init:expr;
+        after $($dep:path),+ $(; $($rest:tt)*)?
+    ) => {
+        $crate::__ro_init_one!($(#[$attr])* $vis $name, $ty, $init, $($dep),+);
+        $($crate::ro_init! { $($rest)* })?
+    };
+    (
+        $(#[$attr:meta])* $vis:vis static $name:ident: RoInit<$ty:ty> = $init:expr;
+        $($rest:tt)*
+    ) => {
+        $crate::__ro_init_one!($(#[$attr])* $vis $name, $ty, $init,);
+        $crate::ro_init! { $($rest)* }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ro_init_one {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $ty:ty, $init:expr, $($dep:path),*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::RoInit<$ty> = $crate::RoInit::__new();
+
+        const _: () = {
+            static DEPS: &[$crate::__Dependency] = &[$($crate::__Dependency::new(&$dep)),*];
+            static INITIALIZER: $crate::__Initializer = $crate::__Initializer::new(
+                concat!(module_path!(), "::", stringify!($name)),
+                &$name,
+                DEPS,
+                init,
+            );
+
+            fn init() {
+                // Evaluated outside of `unsafe`, so the initializer cannot use unsafe operations
+                // unnoticed.
+                let value: $ty = $init;
+                unsafe { $crate::RoInit::__init(&$name, value) };
+            }
+
+            $crate::__ro_ctor! { early {
+                INITIALIZER.register();
+            }}
+
+            $crate::__ro_ctor! {{
+                $crate::__Initializer::run_all();
+            }}
+        };
+    };
+}
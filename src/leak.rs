@@ -0,0 +1,87 @@
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// A read-mostly value that hands out `&'static T` and can be replaced safely, by leaking.
+///
+/// Replaced values are never freed, so references returned by [`RoLeak::get`] stay valid forever
+/// and no reader ever has to be waited for. Reading costs an atomic load. This suits values that
+/// change a handful of times over the lifetime of a process, e.g. configuration, where leaking a
+/// few old versions is a fine price for an entirely safe API.
+///
+/// The `N` most recently replaced values are kept in a graveyard that can be inspected with
+/// [`RoLeak::graveyard`] for diagnostics. Older ones are simply forgotten.
+///
+/// ```
+/// use ro_cell::RoLeak;
+///
+/// static LEVEL: RoLeak<&str, 4> = RoLeak::from_static(&"info");
+///
+/// let old = LEVEL.replace("debug");
+/// assert_eq!(*old, "info");
+/// assert_eq!(*LEVEL.get(), "debug");
+/// assert_eq!(LEVEL.graveyard().collect::<Vec<_>>(), [&"info"]);
+/// ```
+pub struct RoLeak<T: 'static, const N: usize = 0> {
+    ptr: AtomicPtr<T>,
+    graveyard: [AtomicPtr<T>; N],
+    replaced: AtomicUsize,
+    _marker: PhantomData<&'static T>,
+}
+
+impl<T: 'static, const N: usize> RoLeak<T, N> {
+    /// Create a new `RoLeak` holding `value`, which is leaked.
+    pub fn new(value: T) -> Self {
+        Self::from_static(Box::leak(Box::new(value)))
+    }
+
+    /// Create a new `RoLeak` pointing to a value that already lives forever.
+    #[inline]
+    pub const fn from_static(value: &'static T) -> Self {
+        RoLeak {
+            ptr: AtomicPtr::new(value as *const T as *mut T),
+            graveyard: [const { AtomicPtr::new(ptr::null_mut()) }; N],
+            replaced: AtomicUsize::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the current value.
+    #[inline]
+    pub fn get(&self) -> &'static T {
+        unsafe { &*self.ptr.load(Ordering::Acquire) }
+    }
+
+    /// Replace the value, leaking the new one, and return the old one.
+    pub fn replace(&self, value: T) -> &'static T {
+        let new: &'static T = Box::leak(Box::new(value));
+        let old = self
+            .ptr
+            .swap(new as *const T as *mut T, Ordering::AcqRel);
+        if N != 0 {
+            let slot = self.replaced.fetch_add(1, Ordering::Relaxed) % N;
+            self.graveyard[slot].store(old, Ordering::Release);
+        }
+        unsafe { &*old }
+    }
+
+    /// Iterate over the most recently replaced values, newest first.
+    ///
+    /// This is a snapshot intended for diagnostics, and may be inconsistent if the value is
+    /// replaced concurrently.
+    pub fn graveyard(&self) -> impl Iterator<Item = &'static T> + '_ {
+        let replaced = self.replaced.load(Ordering::Relaxed);
+        (0..N.min(replaced)).filter_map(move |i| {
+            let slot = (replaced - 1 - i) % N;
+            unsafe { self.graveyard[slot].load(Ordering::Acquire).as_ref() }
+        })
+    }
+}
+
+impl<T: fmt::Debug + 'static, const N: usize> fmt::Debug for RoLeak<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.get(), f)
+    }
+}
@@ -0,0 +1,123 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::epoch::{self, Epoch, Ticket};
+
+/// A read-mostly value kept in two copies, so readers are never blocked by writers.
+///
+/// This implements the Left-Right algorithm. Readers access the active copy through
+/// [`RoLeftRight::read`], which never waits and costs a few atomic operations on a reader counter
+/// of the current thread, so readers on different cores do not contend. A writer applies its
+/// operation to the inactive copy, makes it the active one, waits for readers of the old copy to
+/// drain, and then applies the same operation to the old copy.
+///
+/// This suits large structures, such as routing tables, that are read all the time and updated
+/// occasionally. Writers are serialized and may have to wait for readers, so they should be rare.
+pub struct RoLeftRight<T> {
+    copies: [UnsafeCell<T>; 2],
+    active: AtomicUsize,
+    epoch: Epoch,
+    writer: AtomicBool,
+}
+
+unsafe impl<T: Send> Send for RoLeftRight<T> {}
+unsafe impl<T: Send + Sync> Sync for RoLeftRight<T> {}
+
+/// A guard for reading a [`RoLeftRight`].
+pub struct LeftRightGuard<'a, T> {
+    value: &'a T,
+    epoch: &'a Epoch,
+    ticket: Ticket,
+}
+
+impl<T> Drop for LeftRightGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.epoch.leave(&self.ticket);
+    }
+}
+
+impl<T> Deref for LeftRightGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LeftRightGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.value, f)
+    }
+}
+
+impl<T> RoLeftRight<T> {
+    /// Create a new `RoLeftRight`, keeping `value` and a clone of it.
+    pub fn new(value: T) -> Self
+    where
+        T: Clone,
+    {
+        RoLeftRight {
+            copies: [UnsafeCell::new(value.clone()), UnsafeCell::new(value)],
+            active: AtomicUsize::new(0),
+            epoch: Epoch::new(),
+            writer: AtomicBool::new(false),
+        }
+    }
+
+    /// Read the value.
+    ///
+    /// Writers wait for the returned guard to be dropped, so it should not be held for long.
+    #[inline]
+    pub fn read(&self) -> LeftRightGuard<'_, T> {
+        let ticket = self.epoch.enter();
+        let active = self.active.load(Ordering::SeqCst);
+        LeftRightGuard {
+            value: unsafe { &*self.copies[active].get() },
+            epoch: &self.epoch,
+            ticket,
+        }
+    }
+
+    /// Modify the value by applying `op`.
+    ///
+    /// `op` is applied once to each copy, and must have the same effect both times, so that the
+    /// copies stay identical. To apply several modifications at once, apply them all in one `op`.
+    ///
+    /// If `op` panics, the copies may diverge.
+    pub fn write(&self, mut op: impl FnMut(&mut T)) {
+        struct Unlock<'a>(&'a AtomicBool);
+
+        impl Drop for Unlock<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        while self
+            .writer
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            epoch::relax();
+        }
+        let _unlock = Unlock(&self.writer);
+
+        // No reader can access the inactive copy: readers that might have seen it active were
+        // waited out by the previous write.
+        let active = self.active.load(Ordering::Relaxed);
+        op(unsafe { &mut *self.copies[active ^ 1].get() });
+        self.active.store(active ^ 1, Ordering::SeqCst);
+        self.epoch.synchronize();
+        op(unsafe { &mut *self.copies[active].get() });
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RoLeftRight<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.read(), f)
+    }
+}
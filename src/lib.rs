@@ -2,14 +2,127 @@
 //!
 //! This crate should only be used for extremely performance critical scenarios; you
 //! would normal want to use `once_cell` instead.
+//!
+//! # Checked mode
+//!
+//! In debug builds, or when the `checked` feature is enabled, each `RoCell` additionally tracks
+//! whether it has been initialized. Reading a cell created by [`RoCell::new_uninit`] before
+//! [`RoCell::init`] is called, or initializing a cell twice, panics instead of causing undefined
+//! behaviour. Release builds without the feature keep the bare `UnsafeCell<MaybeUninit<T>>`
+//! layout and pay nothing for the checks.
+//!
+//! Checked mode can also catch aliasing violations, but only for borrows it can see. References
+//! obtained through `Deref` are not tracked, as their lifetime is invisible to `RoCell`. Borrows
+//! obtained through [`RoCell::borrow`] and [`RoCell::borrow_mut`] are, and mutating a `RoCell`
+//! while such a guard is alive panics.
+//!
+//! The borrow count is shared between threads, so a guard held on one thread also catches a
+//! mutation on another. Checked mode does not, however, track happens-before relations: a
+//! mutation that is merely unsynchronized with an earlier read on another thread goes unnoticed.
+//! Use ThreadSanitizer (`-Zsanitizer=thread`) to catch those.
+//!
+//! # Model checking
+//!
+//! When built with `RUSTFLAGS="--cfg loom"`, the storage of `RoCell` is a [loom] tracked cell, so
+//! the model checker sees every `init`, `replace` and `Deref` and reports accesses that are not
+//! synchronized with each other. This is the same cfg loom itself uses, so a downstream crate
+//! testing its own publication protocol with loom gets the tracked `RoCell` automatically. Under
+//! loom, [`RoCell::new`] and [`RoCell::new_uninit`] are not `const`, as loom's cell cannot be
+//! constructed in a const context.
+//!
+//! [loom]: https://docs.rs/loom
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
-#![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use core::cell::UnsafeCell;
+/// Declare items that need the platform to run static constructors before `main`.
+macro_rules! cfg_ctor {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly",
+                target_os = "illumos",
+                target_vendor = "apple",
+                windows,
+            ))]
+            $item
+        )*
+    };
+}
+
+mod atomic;
+mod borrow;
+mod cell;
+mod dynamic;
+mod epoch;
+mod func;
+mod hook;
+#[cfg(feature = "alloc")]
+mod leak;
+mod left_right;
+mod no_uninit;
+#[cfg(feature = "alloc")]
+mod rcu;
+mod read_mostly;
+#[cfg(all(feature = "std", target_os = "linux"))]
+mod region;
+mod seal;
+mod seq;
+mod slot;
+mod state;
+
+cfg_ctor! {
+    mod ctor;
+    // Apple targets cannot order constructors, which `ro_init!` relies on.
+    #[cfg(not(target_vendor = "apple"))]
+    mod init;
+    #[cfg(feature = "registry")]
+    mod registry;
+}
+
+use core::convert::Infallible;
 use core::fmt;
-use core::mem::MaybeUninit;
+use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ops::Deref;
 
+use cell::UnsafeCell;
+use state::State;
+
+pub use atomic::RoAtomic;
+pub use borrow::{Ref, RefMut};
+pub use dynamic::{RoDyn, SetError};
+pub use func::RoFn;
+pub use hook::{HookToken, RoHook};
+#[cfg(feature = "alloc")]
+pub use leak::RoLeak;
+pub use left_right::{LeftRightGuard, RoLeftRight};
+pub use no_uninit::NoUninit;
+#[cfg(feature = "alloc")]
+pub use rcu::{RcuGuard, RoRcu};
+pub use read_mostly::ReadMostly;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use region::RoRegion;
+pub use seal::{is_sealed, seal};
+pub use seq::RoSeq;
+pub use slot::RoSlot;
+
+cfg_ctor! {
+    #[cfg(not(target_vendor = "apple"))]
+    pub use init::RoInit;
+    #[cfg(not(target_vendor = "apple"))]
+    #[doc(hidden)]
+    pub use init::{Dependency as __Dependency, Initializer as __Initializer};
+    #[cfg(feature = "registry")]
+    pub use registry::{uninitialized, verify_all_initialized, Registration};
+}
+
 /// A cell that is (mostly) readonly.
 ///
 /// It is expected to remain readonly for most time. Some use cases include set-once global
@@ -19,7 +132,7 @@ use core::ops::Deref;
 /// This type should only be used for extremely performance critical scenarios; you
 /// would normal want to use `OnceCell` instead. Mutating `RoCell` in multi-threaded is
 /// extremely dangerous, do not take it lightly.
-pub struct RoCell<T>(UnsafeCell<MaybeUninit<T>>);
+pub struct RoCell<T>(UnsafeCell<MaybeUninit<T>>, State);
 
 unsafe impl<T: Send> Send for RoCell<T> {}
 unsafe impl<T: Sync> Sync for RoCell<T> {}
@@ -27,15 +140,24 @@ unsafe impl<T: Sync> Sync for RoCell<T> {}
 impl<T> Drop for RoCell<T> {
     #[inline]
     fn drop(&mut self) {
-        unsafe { core::ptr::drop_in_place((*self.0.get()).as_mut_ptr()) };
+        if self.1.is_init() {
+            unsafe { core::ptr::drop_in_place(RoCell::as_mut_ptr(self)) };
+        }
     }
 }
 
 impl<T> RoCell<T> {
     /// Create a new `RoCell` that is initialized already.
+    #[cfg(not(loom))]
     #[inline]
     pub const fn new(value: T) -> Self {
-        RoCell(UnsafeCell::new(MaybeUninit::new(value)))
+        RoCell(UnsafeCell::new(MaybeUninit::new(value)), State::new(true))
+    }
+
+    /// Create a new `RoCell` that is initialized already.
+    #[cfg(loom)]
+    pub fn new(value: T) -> Self {
+        RoCell(UnsafeCell::new(MaybeUninit::new(value)), State::new(true))
     }
 
     /// Create a new `RoCell` that is uninitialized.
@@ -45,9 +167,32 @@ impl<T> RoCell<T> {
     /// RoCell can be read in safe code. Therefore, we make its construction unsafe to
     /// permitting uninit value. If `T` needs drop, the caller must ensure that RoCell is
     /// initialised or forgotten before it is dropped.
+    ///
+    /// The same applies to the safe accessors that take the `RoCell` by value or by mutable
+    /// reference: [`RoCell::get_mut`], [`RoCell::set`] and [`RoCell::into_inner`] must only be
+    /// called once the `RoCell` is initialised. `set` drops the old content, so use
+    /// [`RoCell::init`] for a `RoCell` that is not initialised yet.
+    #[cfg(not(loom))]
     #[inline]
     pub const unsafe fn new_uninit() -> Self {
-        RoCell(UnsafeCell::new(MaybeUninit::uninit()))
+        RoCell(UnsafeCell::new(MaybeUninit::uninit()), State::new(false))
+    }
+
+    /// Create a new `RoCell` that is uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// RoCell can be read in safe code. Therefore, we make its construction unsafe to
+    /// permitting uninit value. If `T` needs drop, the caller must ensure that RoCell is
+    /// initialised or forgotten before it is dropped.
+    ///
+    /// The same applies to the safe accessors that take the `RoCell` by value or by mutable
+    /// reference: [`RoCell::get_mut`], [`RoCell::set`] and [`RoCell::into_inner`] must only be
+    /// called once the `RoCell` is initialised. `set` drops the old content, so use
+    /// [`RoCell::init`] for a `RoCell` that is not initialised yet.
+    #[cfg(loom)]
+    pub unsafe fn new_uninit() -> Self {
+        RoCell(UnsafeCell::new(MaybeUninit::uninit()), State::new(false))
     }
 
     /// Initialize a `RoCell`.
@@ -60,9 +205,117 @@ impl<T> RoCell<T> {
     /// No synchronisation is handled by RoCell.
     /// The caller must guarantee that no other threads are accessing this
     /// RoCell and other threads are properly synchronised after the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called. In checked mode, also panics if the `RoCell` is
+    /// already initialized.
     #[inline]
+    #[track_caller]
     pub unsafe fn init(this: &Self, value: T) {
-        core::ptr::write((*this.0.get()).as_mut_ptr(), value);
+        seal::assert_unsealed();
+        RoCell::init_unsealed(this, value);
+    }
+
+    /// Initialize a `RoCell` without checking the seal, for callers that have checked it already
+    /// and must not panic afterwards.
+    #[inline]
+    #[track_caller]
+    pub(crate) unsafe fn init_unsealed(this: &Self, value: T) {
+        this.1.assert_unborrowed();
+        this.1.set_init();
+        core::ptr::write(RoCell::as_mut_ptr(this), value);
+    }
+
+    /// Initialize a `RoCell` with the result of `f`.
+    ///
+    /// If `f` panics, the `RoCell` is left uninitialized, and in checked mode it is marked as
+    /// poisoned so that later reads report the failed initialization.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::init`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called. In checked mode, also panics if the `RoCell` is
+    /// already initialized.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn init_with(this: &Self, f: impl FnOnce() -> T) {
+        match RoCell::try_init(this, || Ok::<_, Infallible>(f())) {
+            Ok(()) => (),
+            Err(err) => match err {},
+        }
+    }
+
+    /// Initialize a `RoCell` with the result of `f`, if it succeeds.
+    ///
+    /// If `f` returns an error, the `RoCell` is left uninitialized and the error is returned. If
+    /// `f` panics, the `RoCell` is left uninitialized, and in checked mode it is marked as
+    /// poisoned so that later reads report the failed initialization.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::init`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called. In checked mode, also panics if the `RoCell` is
+    /// already initialized.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn try_init<E>(this: &Self, f: impl FnOnce() -> Result<T, E>) -> Result<(), E> {
+        seal::assert_unsealed();
+        this.1.assert_unborrowed();
+        this.1.assert_uninit();
+        let value = RoCell::poison_on_panic(this, f)?;
+        core::ptr::write(RoCell::as_mut_ptr(this), value);
+        this.1.set_init();
+        Ok(())
+    }
+
+    /// Initialize a `RoCell` in place.
+    ///
+    /// `f` is given the uninitialized storage of the `RoCell` and must initialize it. This avoids
+    /// moving the value, so large values can be constructed directly inside a static.
+    ///
+    /// If `f` panics, the `RoCell` is left uninitialized, and in checked mode it is marked as
+    /// poisoned so that later reads report the failed initialization.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::init`]. In addition, `f` must fully initialize the value when it returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called. In checked mode, also panics if the `RoCell` is
+    /// already initialized.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn init_in_place(this: &Self, f: impl FnOnce(&mut MaybeUninit<T>)) {
+        seal::assert_unsealed();
+        this.1.assert_unborrowed();
+        this.1.assert_uninit();
+        RoCell::poison_on_panic(this, || f(&mut *RoCell::as_mut_ptr(this).cast()));
+        this.1.set_init();
+    }
+
+    /// Run `f`, marking this `RoCell` as poisoned if it panics.
+    #[inline]
+    fn poison_on_panic<R>(this: &Self, f: impl FnOnce() -> R) -> R {
+        struct Guard<'a>(&'a State);
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.0.set_poisoned();
+            }
+        }
+
+        let guard = Guard(&this.1);
+        let ret = f();
+        core::mem::forget(guard);
+        ret
     }
 
     /// Replace a `RoCell` and return old content.
@@ -75,7 +328,12 @@ impl<T> RoCell<T> {
     /// No synchronisation is handled by `RoCell`.
     /// The caller must guarantee that no other threads are accessing this
     /// RoCell and other threads are properly synchronised after the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called.
     #[inline]
+    #[track_caller]
     pub unsafe fn replace(this: &Self, value: T) -> T {
         core::mem::replace(RoCell::as_mut(this), value)
     }
@@ -92,9 +350,120 @@ impl<T> RoCell<T> {
     /// The caller must guarantee that no other threads are accessing this
     /// RoCell and other threads are properly synchronised after
     /// manipulating the mutable reference.
+    ///
+    /// # Checked mode
+    ///
+    /// The returned reference is not tracked, as its lifetime is invisible to `RoCell`. Checked
+    /// mode therefore does not catch a second overlapping `as_mut`, or a read through `Deref`
+    /// while the reference is alive. Use [`RoCell::borrow_mut`] to have the borrow tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called. In checked mode, also panics if a guard returned by
+    /// [`RoCell::borrow`] or [`RoCell::borrow_mut`] is alive.
     #[inline]
+    #[track_caller]
+    #[allow(clippy::mut_from_ref)]
     pub unsafe fn as_mut(this: &Self) -> &mut T {
-        &mut *(*this.0.get()).as_mut_ptr()
+        seal::assert_unsealed();
+        this.1.assert_init();
+        this.1.assert_unborrowed();
+        &mut *RoCell::as_mut_ptr(this)
+    }
+
+    /// Take the content out of a `RoCell`, leaving it uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must guarantee that there are no other reference to the
+    /// content of this `RoCell` exists.
+    ///
+    /// No synchronisation is handled by `RoCell`.
+    /// The caller must guarantee that no other threads are accessing this
+    /// RoCell and other threads are properly synchronised after the call.
+    ///
+    /// The `RoCell` is left uninitialized, with the same requirements as one created by
+    /// [`RoCell::new_uninit`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn take(this: &Self) -> T {
+        seal::assert_unsealed();
+        this.1.assert_init();
+        this.1.assert_unborrowed();
+        this.1.set_uninit();
+        core::ptr::read(RoCell::as_ptr(this))
+    }
+
+    /// Get a mutable reference to the content of this `RoCell`.
+    ///
+    /// This is safe, as the mutable borrow guarantees exclusive access.
+    #[inline]
+    #[track_caller]
+    pub fn get_mut(this: &mut Self) -> &mut T {
+        this.1.assert_init();
+        unsafe { &mut *RoCell::as_mut_ptr(this) }
+    }
+
+    /// Set the content of this `RoCell`, dropping the old content.
+    ///
+    /// This is safe, as the mutable borrow guarantees exclusive access. The `RoCell` must be
+    /// initialized, see [`RoCell::new_uninit`]; use [`RoCell::init`] otherwise.
+    #[inline]
+    #[track_caller]
+    pub fn set(this: &mut Self, value: T) {
+        *RoCell::get_mut(this) = value;
+    }
+
+    /// Consume this `RoCell` and return its content.
+    #[inline]
+    #[track_caller]
+    pub fn into_inner(this: Self) -> T {
+        this.1.assert_init();
+        let this = ManuallyDrop::new(this);
+        unsafe { core::ptr::read(RoCell::as_ptr(&this)) }
+    }
+
+    /// Borrow the content of this `RoCell` through a guard.
+    ///
+    /// This is equivalent to `Deref`, except that in checked mode the borrow is tracked until the
+    /// guard is dropped, and mutating the `RoCell` in the meantime panics.
+    #[inline]
+    #[track_caller]
+    pub fn borrow(this: &Self) -> Ref<'_, T> {
+        Ref::new(this)
+    }
+
+    /// Mutably borrow the content of this `RoCell` through a guard.
+    ///
+    /// This is equivalent to [`RoCell::as_mut`], except that in checked mode the borrow is
+    /// tracked until the guard is dropped, and reading, borrowing or mutating the `RoCell` in the
+    /// meantime panics.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::as_mut`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`] has been called.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn borrow_mut(this: &Self) -> RefMut<'_, T> {
+        RefMut::new(this)
+    }
+
+    #[inline]
+    fn as_ptr(this: &Self) -> *const T {
+        this.0.with(|ptr| ptr.cast())
+    }
+
+    #[inline]
+    fn as_mut_ptr(this: &Self) -> *mut T {
+        this.0.with_mut(|ptr| ptr.cast())
     }
 }
 
@@ -102,8 +471,11 @@ impl<T> Deref for RoCell<T> {
     type Target = T;
 
     #[inline]
+    #[track_caller]
     fn deref(&self) -> &T {
-        unsafe { &*(*self.0.get()).as_ptr() }
+        self.1.assert_init();
+        self.1.assert_not_mut_borrowed();
+        unsafe { &*RoCell::as_ptr(self) }
     }
 }
 
@@ -0,0 +1,34 @@
+use core::ptr::NonNull;
+
+/// Types that contain no padding or otherwise uninitialized bytes.
+///
+/// Values of such types can be copied byte by byte through atomics, as done by [`RoAtomic`] and
+/// friends. It is implemented for primitive types, raw pointers and arrays thereof. Implement it
+/// for your own `Copy` types, such as fieldless enums or `#[repr(C)]` structs without padding,
+/// if they satisfy the requirement.
+///
+/// # Safety
+///
+/// The type must not contain any padding or uninitialized bytes, in any of its values.
+///
+/// Values are copied between threads by [`RoAtomic`] regardless of `Send` and `Sync`, so it must
+/// also be sound to use copies of a value on several threads at once. This holds for raw pointers,
+/// which are only addresses until dereferenced, but not e.g. for `&Cell<T>`.
+///
+/// [`RoAtomic`]: crate::RoAtomic
+pub unsafe trait NoUninit: Copy {}
+
+macro_rules! impl_no_uninit {
+    ($($ty:ty),*) => {
+        $(unsafe impl NoUninit for $ty {})*
+    };
+}
+
+impl_no_uninit!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+impl_no_uninit!(bool, char, ());
+
+unsafe impl<T: ?Sized> NoUninit for *const T {}
+unsafe impl<T: ?Sized> NoUninit for *mut T {}
+unsafe impl<T: ?Sized> NoUninit for NonNull<T> {}
+unsafe impl<T> NoUninit for Option<NonNull<T>> {}
+unsafe impl<T: NoUninit, const N: usize> NoUninit for [T; N] {}
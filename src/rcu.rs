@@ -0,0 +1,199 @@
+use alloc::boxed::Box;
+use core::fmt;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use crate::epoch::{self, Epoch, Ticket};
+
+/// A read-mostly value that can be replaced safely while being read, using read-copy-update.
+///
+/// Readers enter a read-side critical section with [`RoRcu::read`], which never waits and costs a
+/// few atomic operations on a reader counter of the current thread, so readers on different cores
+/// do not contend. [`RoRcu::replace`] installs a new value, and the old one is only dropped once
+/// every reader that could have seen it is gone, after a grace period.
+///
+/// [`RoRcu::replace`] waits for the grace period itself. [`RoRcu::replace_deferred`] returns
+/// immediately and leaves the old value to be dropped by a later [`RoRcu::synchronize`], like
+/// `call_rcu` does.
+///
+/// ```
+/// use ro_cell::RoRcu;
+///
+/// struct Config {
+///     verbose: bool,
+/// }
+///
+/// let config = RoRcu::new(Config { verbose: false });
+/// assert!(!config.read().verbose);
+///
+/// config.replace(Config { verbose: true });
+/// assert!(config.read().verbose);
+/// ```
+pub struct RoRcu<T> {
+    ptr: AtomicPtr<T>,
+    retired: AtomicPtr<Retired<T>>,
+    epoch: Epoch,
+    writer: AtomicBool,
+}
+
+unsafe impl<T: Send> Send for RoRcu<T> {}
+unsafe impl<T: Send + Sync> Sync for RoRcu<T> {}
+
+/// A value replaced by [`RoRcu::replace_deferred`], waiting for a grace period.
+struct Retired<T> {
+    value: *mut T,
+    next: *mut Retired<T>,
+}
+
+impl<T> Retired<T> {
+    /// Drop all values in the list starting at `head`.
+    unsafe fn free_all(mut head: *mut Self) {
+        while !head.is_null() {
+            let entry = Box::from_raw(head);
+            drop(Box::from_raw(entry.value));
+            head = entry.next;
+        }
+    }
+}
+
+/// A guard for reading a [`RoRcu`].
+pub struct RcuGuard<'a, T> {
+    value: &'a T,
+    epoch: &'a Epoch,
+    ticket: Ticket,
+}
+
+impl<T> Drop for RcuGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.epoch.leave(&self.ticket);
+    }
+}
+
+impl<T> Deref for RcuGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RcuGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.value, f)
+    }
+}
+
+impl<T> RoRcu<T> {
+    /// Create a new `RoRcu` holding `value`.
+    pub fn new(value: T) -> Self {
+        RoRcu {
+            ptr: AtomicPtr::new(Box::into_raw(Box::new(value))),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            epoch: Epoch::new(),
+            writer: AtomicBool::new(false),
+        }
+    }
+
+    /// Read the current value.
+    ///
+    /// The value stays alive, even if replaced, until the returned guard is dropped. Grace periods
+    /// wait for the guard, so it should not be held for long.
+    #[inline]
+    pub fn read(&self) -> RcuGuard<'_, T> {
+        let ticket = self.epoch.enter();
+        let ptr = self.ptr.load(Ordering::SeqCst);
+        RcuGuard {
+            value: unsafe { &*ptr },
+            epoch: &self.epoch,
+            ticket,
+        }
+    }
+
+    /// Replace the value, and drop the old one once all readers that could have seen it are gone.
+    ///
+    /// This waits for a grace period, which also drops values previously passed to
+    /// [`RoRcu::replace_deferred`].
+    pub fn replace(&self, value: T) {
+        self.replace_deferred(value);
+        self.synchronize();
+    }
+
+    /// Replace the value, deferring the drop of the old one to a later [`RoRcu::synchronize`].
+    ///
+    /// This never waits. Old values accumulate until the next grace period, so `synchronize`
+    /// should be called from time to time, e.g. from a background thread.
+    pub fn replace_deferred(&self, value: T) {
+        let old = self
+            .ptr
+            .swap(Box::into_raw(Box::new(value)), Ordering::SeqCst);
+        let retired = Box::into_raw(Box::new(Retired {
+            value: old,
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.retired.load(Ordering::Relaxed);
+        loop {
+            unsafe { (*retired).next = head };
+            match self.retired.compare_exchange_weak(
+                head,
+                retired,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(v) => head = v,
+            }
+        }
+    }
+
+    /// Wait for a grace period, i.e. until all readers that exist at the time of the call are gone,
+    /// and drop the values replaced before the call.
+    pub fn synchronize(&self) {
+        struct Unlock<'a>(&'a AtomicBool);
+
+        impl Drop for Unlock<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        // Values retired after this point are left for the next grace period.
+        let retired = self.retired.swap(ptr::null_mut(), Ordering::Acquire);
+
+        while self
+            .writer
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            epoch::relax();
+        }
+        let unlock = Unlock(&self.writer);
+        self.epoch.synchronize();
+        drop(unlock);
+
+        unsafe { Retired::free_all(retired) };
+    }
+
+    /// Get a mutable reference to the value.
+    ///
+    /// This is safe as the `&mut self` guarantees there are no readers.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut **self.ptr.get_mut() }
+    }
+}
+
+impl<T> Drop for RoRcu<T> {
+    fn drop(&mut self) {
+        unsafe { Retired::free_all(*self.retired.get_mut()) };
+        drop(unsafe { Box::from_raw(*self.ptr.get_mut()) });
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RoRcu<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.read(), f)
+    }
+}
@@ -0,0 +1,97 @@
+use core::fmt;
+use core::ops::Deref;
+
+/// Pads and aligns a value to the size of a cache line.
+///
+/// Statics declared with [`ro_static!`] are wrapped in this type, so that they never share a cache
+/// line with another static. It can also be used directly for read-mostly fields.
+///
+/// The alignment is 128 bytes on x86-64, AArch64 and PowerPC64, where the prefetcher fetches cache
+/// lines in pairs or lines are 128 bytes long, and 64 bytes elsewhere.
+///
+/// [`ro_static!`]: crate::ro_static
+#[cfg_attr(
+    any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64",
+    ),
+    repr(align(128))
+)]
+#[cfg_attr(
+    not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64",
+    )),
+    repr(align(64))
+)]
+pub struct ReadMostly<T>(T);
+
+impl<T> ReadMostly<T> {
+    /// Wrap a value.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        ReadMostly(value)
+    }
+
+    /// Unwrap the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ReadMostly<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReadMostly<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// Declare statics that are grouped into a read-mostly data section.
+///
+/// Each static is wrapped in [`ReadMostly`] so it occupies whole cache lines, and on ELF targets
+/// it is placed in the `.data.read_mostly` section, away from frequently written statics. This
+/// mirrors the kernel's `__read_mostly`.
+///
+/// ```
+/// use ro_cell::{ro_static, RoCell};
+///
+/// ro_static! {
+///     static CONFIG: RoCell<u32> = unsafe { RoCell::new_uninit() };
+/// }
+///
+/// unsafe { RoCell::init(&CONFIG, 42) };
+/// assert_eq!(**CONFIG, 42);
+/// ```
+#[macro_export]
+macro_rules! ro_static {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;)*) => {
+        $(
+            $(#[$attr])*
+            #[cfg_attr(
+                any(
+                    target_os = "linux",
+                    target_os = "android",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd",
+                    target_os = "dragonfly",
+                    target_os = "illumos",
+                    target_os = "none",
+                ),
+                link_section = ".data.read_mostly"
+            )]
+            $vis static $name: $crate::ReadMostly<$ty> = $crate::ReadMostly::new($init);
+        )*
+    };
+}
@@ -0,0 +1,176 @@
+use core::mem::{align_of, size_of};
+use core::ptr::NonNull;
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::RoCell;
+
+/// `mseal` has the same syscall number on all architectures supported by Linux.
+const SYS_MSEAL: libc::c_long = 462;
+
+/// A memory region that holds `RoCell`s and can be made read-only after initialization.
+///
+/// The region is a dedicated, page-aligned anonymous mapping. `RoCell`s are allocated into it with
+/// [`RoRegion::alloc`] and initialized as usual. Once initialization is done,
+/// [`RoRegion::protect`] makes the pages read-only, and seals the mapping where the kernel supports
+/// `mseal`. Any later write to a cell in the region, e.g. through [`RoCell::as_mut`], faults
+/// immediately instead of silently corrupting it.
+///
+/// This is the userspace counterpart to the kernel's `__ro_after_init`.
+///
+/// Cells allocated in the region are never dropped. Types aligned to more than a page cannot be
+/// allocated in it. In checked mode, borrows of cells in the region are not tracked, as tracking
+/// them would write to the protected pages; [`RoCell::borrow`] works as usual.
+pub struct RoRegion {
+    base: NonNull<u8>,
+    len: usize,
+    page_size: usize,
+    state: Mutex<State>,
+}
+
+/// Allocation state of a [`RoRegion`]. The lock also keeps [`RoRegion::protect`] from changing
+/// the protection while a cell is being written.
+struct State {
+    used: usize,
+    protected: bool,
+}
+
+unsafe impl Send for RoRegion {}
+unsafe impl Sync for RoRegion {}
+
+impl RoRegion {
+    /// Map a new region with room for at least `size` bytes.
+    pub fn new(size: usize) -> io::Result<Self> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let len = (size.max(1) + page_size - 1) & !(page_size - 1);
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(RoRegion {
+            base: NonNull::new(ptr.cast()).unwrap(),
+            len,
+            page_size,
+            state: Mutex::new(State {
+                used: 0,
+                protected: false,
+            }),
+        })
+    }
+
+    /// Move `value` into a new `RoCell` allocated in this region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region is full or already protected, or if `T` is aligned to more than a
+    /// page.
+    #[track_caller]
+    pub fn alloc<T>(&self, value: T) -> &RoCell<T> {
+        let mut state = self.lock();
+        let ptr = self.alloc_raw::<T>(&mut state);
+        unsafe {
+            ptr.write(untracked(RoCell::new(value)));
+            &*ptr
+        }
+    }
+
+    /// Allocate a new uninitialized `RoCell` in this region.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::new_uninit`], except that cells in the region are never dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region is full or already protected, or if `T` is aligned to more than a
+    /// page.
+    #[track_caller]
+    pub unsafe fn alloc_uninit<T>(&self) -> &RoCell<T> {
+        let mut state = self.lock();
+        let ptr = self.alloc_raw::<T>(&mut state);
+        ptr.write(untracked(RoCell::new_uninit()));
+        &*ptr
+    }
+
+    /// Reserve room for a `RoCell<T>`. The lock must be held until the cell is written.
+    #[track_caller]
+    fn alloc_raw<T>(&self, state: &mut State) -> *mut RoCell<T> {
+        if state.protected {
+            panic!("RoRegion is already protected");
+        }
+
+        let size = size_of::<RoCell<T>>();
+        let align = align_of::<RoCell<T>>();
+        // Offsets are aligned relative to `base`, which is only page-aligned.
+        if align > self.page_size {
+            panic!("RoRegion cannot hold types aligned to more than a page");
+        }
+        let offset = (state.used + align - 1) & !(align - 1);
+        match offset.checked_add(size) {
+            Some(end) if end <= self.len => state.used = end,
+            _ => panic!("RoRegion is full"),
+        }
+        unsafe { self.base.as_ptr().add(offset).cast() }
+    }
+
+    /// Make the region read-only.
+    ///
+    /// The pages are made read-only with `mprotect`, and then sealed with `mseal` if the kernel
+    /// supports it, so that the protection cannot be lifted again. After this, no more cells can
+    /// be allocated in the region. If `mprotect` fails, the region is left writable and can still
+    /// be allocated from.
+    ///
+    pub fn protect(&self) -> io::Result<()> {
+        let mut state = self.lock();
+        if state.protected {
+            return Ok(());
+        }
+
+        let base = self.base.as_ptr().cast();
+        if unsafe { libc::mprotect(base, self.len, libc::PROT_READ) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        state.protected = true;
+        if unsafe { libc::syscall(SYS_MSEAL, base, self.len, 0) } != 0 {
+            let err = io::Error::last_os_error();
+            // Kernels before 6.10 do not have `mseal`; page protection alone still applies.
+            if err.raw_os_error() != Some(libc::ENOSYS) {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the region has been made read-only by [`RoRegion::protect`].
+    #[inline]
+    pub fn is_protected(&self) -> bool {
+        self.lock().protected
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is consistent even if a thread panicked while holding the lock.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Stop tracking borrows of `cell`, so that reading it never writes to the region.
+fn untracked<T>(cell: RoCell<T>) -> RoCell<T> {
+    cell.1.set_untracked();
+    cell
+}
+
+impl Drop for RoRegion {
+    fn drop(&mut self) {
+        // This fails for sealed mappings, which then stay mapped until the process exits.
+        unsafe { libc::munmap(self.base.as_ptr().cast(), self.len) };
+    }
+}
@@ -0,0 +1,152 @@
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::state::State;
+use crate::RoCell;
+
+static HEAD: AtomicPtr<Registration> = AtomicPtr::new(ptr::null_mut());
+
+/// A `RoCell` registered by [`ro_uninit!`].
+///
+/// [`ro_uninit!`]: crate::ro_uninit
+pub struct Registration {
+    name: &'static str,
+    type_name: fn() -> &'static str,
+    state: &'static State,
+    next: AtomicPtr<Registration>,
+}
+
+impl Registration {
+    #[doc(hidden)]
+    pub const fn new<T>(name: &'static str, cell: &'static RoCell<T>) -> Self {
+        Registration {
+            name,
+            type_name: core::any::type_name::<T>,
+            state: &cell.1,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn register(&'static self) {
+        let mut head = HEAD.load(Ordering::Relaxed);
+        loop {
+            self.next.store(head, Ordering::Relaxed);
+            match HEAD.compare_exchange_weak(
+                head,
+                self as *const Self as *mut Self,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(v) => head = v,
+            }
+        }
+    }
+
+    /// The path of the registered static.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The name of the type stored in the registered `RoCell`.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+
+    /// Check if the registered `RoCell` has been initialized.
+    #[inline]
+    pub fn is_initialized(&self) -> bool {
+        self.state.is_init()
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("name", &self.name())
+            .field("type_name", &self.type_name())
+            .field("initialized", &self.is_initialized())
+            .finish()
+    }
+}
+
+/// Iterate over all registered `RoCell`s that have not been initialized yet.
+pub fn uninitialized() -> impl Iterator<Item = &'static Registration> {
+    let mut next = HEAD.load(Ordering::Acquire);
+    core::iter::from_fn(move || {
+        let reg = unsafe { next.as_ref()? };
+        next = reg.next.load(Ordering::Relaxed);
+        Some(reg)
+    })
+    .filter(|reg| !reg.is_initialized())
+}
+
+/// Check that all `RoCell`s registered with [`ro_uninit!`] have been initialized.
+///
+/// This is intended to be called at the end of startup.
+///
+/// # Panics
+///
+/// Panics with a list of all registered `RoCell`s that have not been initialized, if any.
+///
+/// [`ro_uninit!`]: crate::ro_uninit
+#[track_caller]
+pub fn verify_all_initialized() {
+    struct Missing;
+
+    impl fmt::Display for Missing {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for reg in uninitialized() {
+                write!(f, "\n    {}: RoCell<{}>", reg.name(), reg.type_name())?;
+            }
+            Ok(())
+        }
+    }
+
+    if uninitialized().next().is_some() {
+        panic!("uninitialized RoCells:{}", Missing);
+    }
+}
+
+/// Declare uninitialized `RoCell` statics that are registered for [`verify_all_initialized`].
+///
+/// Each static is created uninitialized, as with [`RoCell::new_uninit`], and must be initialized
+/// with [`RoCell::init`] as usual. The registration happens before `main`, so a later call to
+/// [`verify_all_initialized`] reports every one that was missed.
+///
+/// ```
+/// use ro_cell::{ro_uninit, RoCell};
+///
+/// ro_uninit! {
+///     static PORT: RoCell<u16>;
+/// }
+///
+/// fn main() {
+///     unsafe { RoCell::init(&PORT, 8080) };
+///     ro_cell::verify_all_initialized();
+/// }
+/// ```
+///
+/// The `registry` feature enables checked mode, so reading such a static before it is initialized
+/// panics instead of causing undefined behaviour.
+#[macro_export]
+macro_rules! ro_uninit {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: RoCell<$ty:ty>;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::RoCell<$ty> = unsafe { $crate::RoCell::new_uninit() };
+
+            $crate::__ro_ctor! {{
+                static REGISTRATION: $crate::Registration = $crate::Registration::new(
+                    concat!(module_path!(), "::", stringify!($name)),
+                    &$name,
+                );
+                REGISTRATION.register();
+            }}
+        )*
+    };
+}
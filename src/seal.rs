@@ -0,0 +1,38 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+static SEALED: AtomicBool = AtomicBool::new(false);
+
+/// Seal all `RoCell`s in the process.
+///
+/// After this is called, all functions that mutate a `RoCell` through a shared reference, e.g.
+/// [`RoCell::init`], [`RoCell::replace`] and [`RoCell::as_mut`], panic instead of mutating. This
+/// is intended to be called once initialization is complete, before worker threads are started.
+/// Sealing is permanent.
+///
+/// There are no variants of these functions that return an error instead. Mutating a sealed
+/// `RoCell` is a bug, like initializing one twice. Code that has to cope with a sealed process can
+/// check [`is_sealed`] first.
+///
+/// Reading a `RoCell` is not affected and costs nothing extra.
+///
+/// [`RoCell::init`]: crate::RoCell::init
+/// [`RoCell::replace`]: crate::RoCell::replace
+/// [`RoCell::as_mut`]: crate::RoCell::as_mut
+#[inline]
+pub fn seal() {
+    SEALED.store(true, Ordering::Release);
+}
+
+/// Check if [`seal`] has been called.
+#[inline]
+pub fn is_sealed() -> bool {
+    SEALED.load(Ordering::Acquire)
+}
+
+#[inline]
+#[track_caller]
+pub(crate) fn assert_unsealed() {
+    if SEALED.load(Ordering::Relaxed) {
+        panic!("RoCell mutated after ro_cell::seal()");
+    }
+}
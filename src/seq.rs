@@ -0,0 +1,144 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::{align_of, size_of, MaybeUninit};
+use core::sync::atomic::{self, AtomicPtr, AtomicU8, AtomicUsize, Ordering};
+
+use crate::NoUninit;
+
+/// A read-mostly value of a small `Copy` type, protected by a sequence lock.
+///
+/// `RoSeq` is for values too large for [`RoAtomic`], such as a clock calibration struct or a
+/// routing tuple, that need to be updated at runtime. Readers take an optimistic snapshot and
+/// retry if a write happened in the meantime, so they never block writers and do not write to
+/// shared memory. [`RoSeq::write`] is safe, and concurrent writers are serialized.
+///
+/// The value is copied with relaxed atomic accesses, a word at a time where alignment permits, so
+/// a read racing with a write is not a data race.
+///
+/// [`RoAtomic`]: crate::RoAtomic
+pub struct RoSeq<T> {
+    seq: AtomicUsize,
+    value: UnsafeCell<T>,
+}
+
+// As with `RoAtomic`, this does not depend on `T: Send + Sync`, so `RoSeq` works with raw
+// pointers. Readers only ever get copies of the value, which `NoUninit` requires to be safe to
+// share.
+unsafe impl<T: NoUninit> Send for RoSeq<T> {}
+unsafe impl<T: NoUninit> Sync for RoSeq<T> {}
+
+impl<T: NoUninit> RoSeq<T> {
+    /// Create a new `RoSeq`.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        RoSeq {
+            seq: AtomicUsize::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Read a consistent snapshot of the value.
+    #[inline]
+    pub fn read(&self) -> T {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 0 {
+                let value = unsafe { atomic_read(self.value.get()) };
+                atomic::fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) == seq {
+                    // No write overlapped the copy, so it is a valid value.
+                    return unsafe { value.assume_init() };
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Replace the value.
+    pub fn write(&self, value: T) {
+        let mut seq = self.seq.load(Ordering::Relaxed);
+        loop {
+            if seq & 1 == 0 {
+                match self.seq.compare_exchange_weak(
+                    seq,
+                    seq.wrapping_add(1),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(v) => seq = v,
+                }
+            } else {
+                core::hint::spin_loop();
+                seq = self.seq.load(Ordering::Relaxed);
+            }
+        }
+        atomic::fence(Ordering::Release);
+        unsafe { atomic_write(self.value.get(), value) };
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Get a mutable reference to the value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /// Consume the `RoSeq` and return its value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: NoUninit + fmt::Debug> fmt::Debug for RoSeq<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.read(), f)
+    }
+}
+
+/// Whether `T` can be copied a pointer-sized word at a time.
+#[inline]
+const fn word_sized<T>() -> bool {
+    align_of::<T>() >= align_of::<*mut ()>() && size_of::<T>() % size_of::<*mut ()>() == 0
+}
+
+/// Read a value with relaxed atomic loads.
+///
+/// Words are copied as pointers, rather than integers, so that pointers keep their provenance.
+/// The copy is torn if it races with a write, and may then not be a valid `T`, e.g. for `char`.
+/// It must only be assumed initialized once it is known not to have raced.
+unsafe fn atomic_read<T: NoUninit>(src: *const T) -> MaybeUninit<T> {
+    let mut value = MaybeUninit::<T>::uninit();
+    if word_sized::<T>() {
+        let src = src as *const AtomicPtr<()>;
+        let dst = value.as_mut_ptr() as *mut *mut ();
+        for i in 0..size_of::<T>() / size_of::<*mut ()>() {
+            dst.add(i).write((*src.add(i)).load(Ordering::Relaxed));
+        }
+    } else {
+        let src = src as *const AtomicU8;
+        let dst = value.as_mut_ptr() as *mut u8;
+        for i in 0..size_of::<T>() {
+            dst.add(i).write((*src.add(i)).load(Ordering::Relaxed));
+        }
+    }
+    value
+}
+
+/// Write a value with relaxed atomic stores.
+unsafe fn atomic_write<T: NoUninit>(dst: *mut T, value: T) {
+    if word_sized::<T>() {
+        let src = &value as *const T as *const *mut ();
+        let dst = dst as *const AtomicPtr<()>;
+        for i in 0..size_of::<T>() / size_of::<*mut ()>() {
+            (*dst.add(i)).store(src.add(i).read(), Ordering::Relaxed);
+        }
+    } else {
+        let src = &value as *const T as *const u8;
+        let dst = dst as *const AtomicU8;
+        for i in 0..size_of::<T>() {
+            (*dst.add(i)).store(src.add(i).read(), Ordering::Relaxed);
+        }
+    }
+}
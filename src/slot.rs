@@ -0,0 +1,159 @@
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::RoCell;
+
+/// A `RoCell` that knows whether it is initialized.
+///
+/// `RoSlot` carries an initialization flag in all builds, so unlike a `RoCell` created by
+/// [`RoCell::new_uninit`], it may be dropped without ever being initialized. This makes it
+/// suitable as a read-mostly field of an ordinary struct, not just for leaked statics. `Debug`
+/// prints `<uninit>` for an uninitialized slot.
+///
+/// Reading a `RoSlot` costs the same as reading a `RoCell`; the flag is only consulted on
+/// initialization, drop and formatting.
+pub struct RoSlot<T> {
+    cell: ManuallyDrop<RoCell<T>>,
+    init: AtomicBool,
+}
+
+impl<T> Drop for RoSlot<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if *self.init.get_mut() {
+            unsafe { ManuallyDrop::drop(&mut self.cell) };
+        }
+    }
+}
+
+impl<T> RoSlot<T> {
+    /// Create a new `RoSlot` that is initialized already.
+    #[cfg(not(loom))]
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        RoSlot {
+            cell: ManuallyDrop::new(RoCell::new(value)),
+            init: AtomicBool::new(true),
+        }
+    }
+
+    /// Create a new `RoSlot` that is initialized already.
+    #[cfg(loom)]
+    pub fn new(value: T) -> Self {
+        RoSlot {
+            cell: ManuallyDrop::new(RoCell::new(value)),
+            init: AtomicBool::new(true),
+        }
+    }
+
+    /// Create a new `RoSlot` that is uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// RoSlot can be read in safe code. Therefore, we make its construction unsafe to
+    /// permitting uninit value. The caller must ensure that RoSlot is initialised before it is
+    /// read. Unlike [`RoCell::new_uninit`], it may be dropped while uninitialised.
+    #[cfg(not(loom))]
+    #[inline]
+    pub const unsafe fn new_uninit() -> Self {
+        RoSlot {
+            cell: ManuallyDrop::new(RoCell::new_uninit()),
+            init: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a new `RoSlot` that is uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// RoSlot can be read in safe code. Therefore, we make its construction unsafe to
+    /// permitting uninit value. The caller must ensure that RoSlot is initialised before it is
+    /// read. Unlike [`RoCell::new_uninit`], it may be dropped while uninitialised.
+    #[cfg(loom)]
+    pub unsafe fn new_uninit() -> Self {
+        RoSlot {
+            cell: ManuallyDrop::new(RoCell::new_uninit()),
+            init: AtomicBool::new(false),
+        }
+    }
+
+    /// Check if this `RoSlot` is initialized.
+    #[inline]
+    pub fn is_initialized(this: &Self) -> bool {
+        this.init.load(Ordering::Acquire)
+    }
+
+    /// Initialize a `RoSlot`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::init`]. Unlike `RoCell`, initializing a `RoSlot` that is already
+    /// initialized drops the old value instead of leaking it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`](crate::seal) has been called.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn init(this: &Self, value: T) {
+        if Self::is_initialized(this) {
+            drop(RoCell::replace(&this.cell, value));
+        } else {
+            RoCell::init(&this.cell, value);
+            this.init.store(true, Ordering::Release);
+        }
+    }
+
+    /// Replace a `RoSlot` and return old content.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::replace`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`](crate::seal) has been called.
+    #[inline]
+    #[track_caller]
+    pub unsafe fn replace(this: &Self, value: T) -> T {
+        RoCell::replace(&this.cell, value)
+    }
+
+    /// Get a mutable reference to this `RoSlot`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`RoCell::as_mut`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`seal`](crate::seal) has been called.
+    #[inline]
+    #[track_caller]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn as_mut(this: &Self) -> &mut T {
+        RoCell::as_mut(&this.cell)
+    }
+}
+
+impl<T> Deref for RoSlot<T> {
+    type Target = T;
+
+    #[inline]
+    #[track_caller]
+    fn deref(&self) -> &T {
+        &self.cell
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RoSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if RoSlot::is_initialized(self) {
+            fmt::Debug::fmt(self.deref(), f)
+        } else {
+            f.write_str("<uninit>")
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Initialization state tracking.
+//!
+//! In checked builds (debug builds or with the `checked` feature enabled), each `RoCell` carries
+//! a flag recording whether it has been initialized, or poisoned by a panicking initializer, and
+//! a count of live borrow guards, so misuse can be turned into a panic. Cells that must not be
+//! written to, e.g. those in a protected `RoRegion`, can opt out of the borrow count. In other builds `State`
+//! is zero-sized and all checks compile to nothing.
+
+#[cfg(any(debug_assertions, feature = "checked"))]
+mod imp {
+    use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+    const UNINIT: u8 = 0;
+    const INIT: u8 = 1;
+    const POISONED: u8 = 2;
+
+    /// Borrow count value used to mark an exclusive borrow.
+    const EXCLUSIVE: usize = usize::MAX;
+    /// Borrow count value used to mark a cell whose borrows are not tracked.
+    const UNTRACKED: usize = EXCLUSIVE - 1;
+
+    pub(crate) struct State {
+        init: AtomicU8,
+        borrow: AtomicUsize,
+    }
+
+    impl State {
+        #[inline]
+        pub(crate) const fn new(init: bool) -> Self {
+            State {
+                init: AtomicU8::new(if init { INIT } else { UNINIT }),
+                borrow: AtomicUsize::new(0),
+            }
+        }
+
+        #[inline]
+        pub(crate) fn is_init(&self) -> bool {
+            self.init.load(Ordering::Acquire) == INIT
+        }
+
+        #[inline]
+        #[track_caller]
+        pub(crate) fn assert_init(&self) {
+            match self.init.load(Ordering::Acquire) {
+                INIT => (),
+                POISONED => panic!("RoCell poisoned by a panicking initializer"),
+                _ => panic!("RoCell accessed before initialization"),
+            }
+        }
+
+        /// Panic if the cell is initialized.
+        #[inline]
+        #[track_caller]
+        pub(crate) fn assert_uninit(&self) {
+            if self.is_init() {
+                panic!("RoCell initialized twice");
+            }
+        }
+
+        /// Mark the cell as initialized. Panics if it already is.
+        #[inline]
+        #[track_caller]
+        pub(crate) fn set_init(&self) {
+            // Mutation is externally synchronized, so no read-modify-write is needed here.
+            self.assert_uninit();
+            self.init.store(INIT, Ordering::Release);
+        }
+
+        /// Mark the cell as uninitialized.
+        #[inline]
+        pub(crate) fn set_uninit(&self) {
+            self.init.store(UNINIT, Ordering::Release);
+        }
+
+        /// Mark the cell as poisoned by a panic during initialization.
+        #[inline]
+        pub(crate) fn set_poisoned(&self) {
+            self.init.store(POISONED, Ordering::Release);
+        }
+
+        /// Stop tracking borrows of the cell, so that borrowing it never writes to it.
+        #[cfg(all(feature = "std", target_os = "linux"))]
+        #[inline]
+        pub(crate) fn set_untracked(&self) {
+            self.borrow.store(UNTRACKED, Ordering::Relaxed);
+        }
+
+        /// Panic if there are any live borrow guards.
+        #[inline]
+        #[track_caller]
+        pub(crate) fn assert_unborrowed(&self) {
+            match self.borrow.load(Ordering::Acquire) {
+                0 | UNTRACKED => (),
+                EXCLUSIVE => panic!("RoCell mutated while mutably borrowed"),
+                _ => panic!("RoCell mutated while borrowed"),
+            }
+        }
+
+        /// Panic if there is a live exclusive borrow guard.
+        #[inline]
+        #[track_caller]
+        pub(crate) fn assert_not_mut_borrowed(&self) {
+            if self.borrow.load(Ordering::Acquire) == EXCLUSIVE {
+                panic!("RoCell read while mutably borrowed");
+            }
+        }
+
+        #[inline]
+        #[track_caller]
+        pub(crate) fn acquire_shared(&self) {
+            let mut cur = self.borrow.load(Ordering::Relaxed);
+            loop {
+                if cur == UNTRACKED {
+                    return;
+                }
+                if cur >= UNTRACKED - 1 {
+                    panic!("RoCell already mutably borrowed");
+                }
+                match self.borrow.compare_exchange_weak(
+                    cur,
+                    cur + 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return,
+                    Err(v) => cur = v,
+                }
+            }
+        }
+
+        #[inline]
+        pub(crate) fn release_shared(&self) {
+            if self.borrow.load(Ordering::Relaxed) != UNTRACKED {
+                self.borrow.fetch_sub(1, Ordering::Release);
+            }
+        }
+
+        #[inline]
+        #[track_caller]
+        pub(crate) fn acquire_exclusive(&self) {
+            match self
+                .borrow
+                .compare_exchange(0, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) | Err(UNTRACKED) => (),
+                Err(_) => panic!("RoCell already borrowed"),
+            }
+        }
+
+        #[inline]
+        pub(crate) fn release_exclusive(&self) {
+            if self.borrow.load(Ordering::Relaxed) != UNTRACKED {
+                self.borrow.store(0, Ordering::Release);
+            }
+        }
+    }
+}
+
+#[cfg(not(any(debug_assertions, feature = "checked")))]
+mod imp {
+    pub(crate) struct State;
+
+    impl State {
+        #[inline]
+        pub(crate) const fn new(_init: bool) -> Self {
+            State
+        }
+
+        #[inline]
+        pub(crate) fn is_init(&self) -> bool {
+            true
+        }
+
+        #[inline]
+        pub(crate) fn assert_init(&self) {}
+
+        #[inline]
+        pub(crate) fn assert_uninit(&self) {}
+
+        #[inline]
+        pub(crate) fn set_init(&self) {}
+
+        #[inline]
+        pub(crate) fn set_uninit(&self) {}
+
+        #[inline]
+        pub(crate) fn set_poisoned(&self) {}
+
+        #[cfg(all(feature = "std", target_os = "linux"))]
+        #[inline]
+        pub(crate) fn set_untracked(&self) {}
+
+        #[inline]
+        pub(crate) fn assert_unborrowed(&self) {}
+
+        #[inline]
+        pub(crate) fn assert_not_mut_borrowed(&self) {}
+
+        #[inline]
+        pub(crate) fn acquire_shared(&self) {}
+
+        #[inline]
+        pub(crate) fn release_shared(&self) {}
+
+        #[inline]
+        pub(crate) fn acquire_exclusive(&self) {}
+
+        #[inline]
+        pub(crate) fn release_exclusive(&self) {}
+    }
+}
+
+pub(crate) use imp::State;
@@ -0,0 +1,102 @@
+//! Tests for `RoAtomic`, round-tripping each kind of `NoUninit` type through it.
+
+use std::fmt::Debug;
+use std::ptr::{self, NonNull};
+use std::sync::atomic::Ordering;
+use std::thread;
+
+use ro_cell::{NoUninit, RoAtomic};
+
+/// Store `a` and `b` through every accessor and check that they come back unchanged.
+fn round_trip<T: NoUninit + PartialEq + Debug>(a: T, b: T) {
+    let atomic = RoAtomic::new(a);
+    assert_eq!(atomic.get(), a);
+    atomic.set(b);
+    assert_eq!(atomic.load(Ordering::Acquire), b);
+    atomic.store(a, Ordering::SeqCst);
+    assert_eq!(atomic.load(Ordering::SeqCst), a);
+    assert_eq!(atomic.swap(b, Ordering::AcqRel), a);
+    assert_eq!(format!("{:?}", atomic), format!("{:?}", b));
+    assert_eq!(atomic.into_inner(), b);
+}
+
+#[test]
+fn integers() {
+    round_trip(0x12u8, 0xFE);
+    round_trip(-2i8, 127);
+    round_trip(0x1234u16, 0xFEDC);
+    round_trip(-2i16, i16::MAX);
+    round_trip(0x1234_5678u32, u32::MAX);
+    round_trip(i32::MIN, -1);
+    round_trip(usize::MAX, 1);
+    round_trip(isize::MIN, isize::MAX);
+    #[cfg(target_pointer_width = "64")]
+    round_trip(u64::MAX - 1, 1);
+}
+
+#[test]
+fn floats() {
+    round_trip(1.5f32, -0.0);
+    round_trip(f32::MIN_POSITIVE, f32::INFINITY);
+    #[cfg(target_pointer_width = "64")]
+    round_trip(1.5f64, f64::NEG_INFINITY);
+}
+
+#[test]
+fn bool_char_unit() {
+    round_trip(false, true);
+    round_trip('a', '\u{10FFFF}');
+    round_trip((), ());
+}
+
+#[test]
+fn arrays() {
+    round_trip([1u8, 2, 3], [0xFD, 0xFE, 0xFF]);
+    round_trip([0x1234u16, 0x5678], [0xFFFF, 0]);
+    round_trip([true, false], [false, true]);
+    round_trip([(); 8], [(); 8]);
+}
+
+#[test]
+fn pointers_keep_provenance() {
+    let mut a = 1u32;
+    let mut b = 2u32;
+    let pa = &mut a as *mut u32;
+    let pb = &mut b as *mut u32;
+
+    round_trip(pa, pb);
+    round_trip(pa as *const u32, ptr::null());
+    round_trip(NonNull::new(pa).unwrap(), NonNull::new(pb).unwrap());
+    round_trip(NonNull::new(pa), None);
+
+    // The pointers read back must still be usable to access the pointee, which Miri checks.
+    let atomic = RoAtomic::new(pa);
+    unsafe { *atomic.get() += 10 };
+    unsafe { *atomic.swap(pb, Ordering::Relaxed) += 10 };
+    unsafe { *atomic.into_inner() += 10 };
+    assert_eq!((a, b), (21, 12));
+}
+
+#[test]
+fn static_pointer() {
+    static VALUE: RoAtomic<*mut u32> = RoAtomic::new(ptr::null_mut());
+
+    assert!(VALUE.get().is_null());
+    let leaked = Box::leak(Box::new(7u32));
+    VALUE.set(leaked);
+    let shared = thread::spawn(|| unsafe { *VALUE.get() }).join().unwrap();
+    assert_eq!(shared, 7);
+    drop(unsafe { Box::from_raw(VALUE.swap(ptr::null_mut(), Ordering::AcqRel)) });
+}
+
+#[test]
+#[should_panic]
+fn load_with_release_panics() {
+    RoAtomic::new(1u8).load(Ordering::Release);
+}
+
+#[test]
+#[should_panic]
+fn store_with_acquire_panics() {
+    RoAtomic::new(1u8).store(2, Ordering::Acquire);
+}
@@ -0,0 +1,56 @@
+//! Tests for when `RoCell` drops its content.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ro_cell::RoCell;
+
+/// A value that counts how often values of its kind have been dropped.
+struct Counted<'a>(usize, &'a AtomicUsize);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn get_mut_drops_nothing() {
+    let drops = AtomicUsize::new(0);
+    let mut cell = RoCell::new(Counted(1, &drops));
+    RoCell::get_mut(&mut cell).0 = 2;
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(cell.0, 2);
+    drop(cell);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn set_drops_old_value() {
+    let drops = AtomicUsize::new(0);
+    let mut cell = RoCell::new(Counted(1, &drops));
+    RoCell::set(&mut cell, Counted(2, &drops));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(cell.0, 2);
+    drop(cell);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn into_inner_does_not_drop() {
+    let drops = AtomicUsize::new(0);
+    let cell = RoCell::new(Counted(1, &drops));
+    let value = RoCell::into_inner(cell);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(value.0, 1);
+    drop(value);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn init_then_into_inner() {
+    let drops = AtomicUsize::new(0);
+    let cell = unsafe { RoCell::new_uninit() };
+    unsafe { RoCell::init(&cell, Counted(1, &drops)) };
+    drop(RoCell::into_inner(cell));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,98 @@
+//! Tests for the panics of checked mode.
+//!
+//! Checked mode is always enabled in debug builds, so these run with a plain `cargo test`.
+
+#![cfg(any(debug_assertions, feature = "checked"))]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use ro_cell::RoCell;
+
+#[test]
+#[should_panic(expected = "RoCell accessed before initialization")]
+fn read_before_init() {
+    let cell = unsafe { RoCell::<u32>::new_uninit() };
+    let _ = *cell;
+}
+
+#[test]
+#[should_panic(expected = "RoCell initialized twice")]
+fn init_twice() {
+    let cell = RoCell::new(1);
+    unsafe { RoCell::init(&cell, 2) };
+}
+
+#[test]
+#[should_panic(expected = "RoCell accessed before initialization")]
+fn read_after_take() {
+    let cell = RoCell::new(1);
+    assert_eq!(unsafe { RoCell::take(&cell) }, 1);
+    let _ = *cell;
+}
+
+#[test]
+#[should_panic(expected = "RoCell mutated while borrowed")]
+fn replace_while_borrowed() {
+    let cell = RoCell::new(1);
+    let _guard = RoCell::borrow(&cell);
+    unsafe { RoCell::replace(&cell, 2) };
+}
+
+#[test]
+#[should_panic(expected = "RoCell mutated while mutably borrowed")]
+fn as_mut_while_mutably_borrowed() {
+    let cell = RoCell::new(1);
+    let _guard = unsafe { RoCell::borrow_mut(&cell) };
+    unsafe { RoCell::as_mut(&cell) };
+}
+
+#[test]
+#[should_panic(expected = "RoCell read while mutably borrowed")]
+fn read_while_mutably_borrowed() {
+    let cell = RoCell::new(1);
+    let _guard = unsafe { RoCell::borrow_mut(&cell) };
+    let _ = *cell;
+}
+
+#[test]
+#[should_panic(expected = "RoCell already mutably borrowed")]
+fn borrow_while_mutably_borrowed() {
+    let cell = RoCell::new(1);
+    let _guard = unsafe { RoCell::borrow_mut(&cell) };
+    RoCell::borrow(&cell);
+}
+
+#[test]
+#[should_panic(expected = "RoCell already borrowed")]
+fn borrow_mut_while_borrowed() {
+    let cell = RoCell::new(1);
+    let _guard = RoCell::borrow(&cell);
+    unsafe { RoCell::borrow_mut(&cell) };
+}
+
+#[test]
+fn borrow_released_on_drop() {
+    let cell = RoCell::new(1);
+    drop(RoCell::borrow(&cell));
+    drop(unsafe { RoCell::borrow_mut(&cell) });
+    assert_eq!(unsafe { RoCell::replace(&cell, 2) }, 1);
+}
+
+#[test]
+#[should_panic(expected = "RoCell poisoned by a panicking initializer")]
+fn read_after_panicking_init() {
+    let cell = unsafe { RoCell::<u32>::new_uninit() };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe {
+        RoCell::init_with(&cell, || panic!())
+    }));
+    assert!(result.is_err());
+    let _ = *cell;
+}
+
+#[test]
+fn init_after_failed_try_init() {
+    let cell = unsafe { RoCell::<u32>::new_uninit() };
+    assert_eq!(unsafe { RoCell::try_init(&cell, || Err(())) }, Err(()));
+    unsafe { RoCell::init(&cell, 1) };
+    assert_eq!(*cell, 1);
+}
@@ -0,0 +1,55 @@
+//! Tests for `RoDyn`.
+
+use std::fmt::Debug;
+use std::thread;
+
+use ro_cell::RoDyn;
+
+#[test]
+fn default_until_set() {
+    let slot = RoDyn::<dyn Debug + Sync>::new(&"default");
+    assert!(!slot.is_set());
+    assert_eq!(format!("{:?}", slot), "\"default\"");
+
+    slot.set(&"set");
+    assert!(slot.is_set());
+    assert_eq!(format!("{:?}", slot), "\"set\"");
+}
+
+#[test]
+fn try_set_only_once() {
+    let slot = RoDyn::<str>::new("default");
+    assert!(slot.try_set("first").is_ok());
+    let err = slot.try_set("second").unwrap_err();
+    assert_eq!(err.to_string(), "RoDyn is already set");
+    assert_eq!(slot.get(), "first");
+}
+
+#[test]
+#[should_panic(expected = "RoDyn is already set")]
+fn set_twice() {
+    let slot = RoDyn::<str>::new("default");
+    slot.set("first");
+    slot.set("second");
+}
+
+#[test]
+fn concurrent_try_set() {
+    static VALUES: [usize; 4] = [1, 2, 3, 4];
+    let slot = RoDyn::<usize>::new(&0);
+
+    let slot = &slot;
+
+    let winners = thread::scope(|s| {
+        let threads: Vec<_> = VALUES
+            .iter()
+            .map(|value| s.spawn(move || slot.try_set(value).is_ok()))
+            .collect();
+        threads
+            .into_iter()
+            .map(|t| t.join().unwrap() as usize)
+            .sum::<usize>()
+    });
+    assert_eq!(winners, 1);
+    assert!(VALUES.contains(slot.get()));
+}
@@ -0,0 +1,77 @@
+//! Tests for `ro_fn!`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ro_cell::ro_fn;
+
+fn double(x: u32) -> u32 {
+    x * 2
+}
+
+fn triple(x: u32) -> u32 {
+    x * 3
+}
+
+/// # Safety
+///
+/// Always safe; `unsafe` only to check that unsafe implementations are accepted.
+unsafe fn square(x: u32) -> u32 {
+    x * x
+}
+
+static LAZY_SELECTS: AtomicUsize = AtomicUsize::new(0);
+static EAGER_SELECTS: AtomicUsize = AtomicUsize::new(0);
+static UNSAFE_SELECTS: AtomicUsize = AtomicUsize::new(0);
+
+/// A predicate that counts how often it is evaluated, and never holds.
+fn select(count: &AtomicUsize) -> bool {
+    count.fetch_add(1, Ordering::Relaxed);
+    false
+}
+
+ro_fn! {
+    fn lazy(x: u32) -> u32 = {
+        if select(&LAZY_SELECTS) => double,
+        else => triple,
+    }
+
+    fn eager(x: u32) -> u32 = {
+        if select(&EAGER_SELECTS) => double,
+        else => triple,
+    }
+}
+
+ro_fn! {
+    fn unchecked(x: u32) -> u32 = unsafe {
+        if UNSAFE_SELECTS.fetch_add(1, Ordering::Relaxed) == 0 => square,
+        else => double,
+    }
+}
+
+#[test]
+fn first_call_resolves_once() {
+    assert_eq!(LAZY_SELECTS.load(Ordering::Relaxed), 0);
+    for x in 0..4 {
+        assert_eq!(lazy(x), x * 3);
+    }
+    assert_eq!(LAZY_SELECTS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn resolve_without_calling() {
+    eager::resolve();
+    assert_eq!(EAGER_SELECTS.load(Ordering::Relaxed), 1);
+    for x in 0..4 {
+        assert_eq!(eager(x), x * 3);
+    }
+    assert_eq!(EAGER_SELECTS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn unsafe_implementations() {
+    unchecked::resolve();
+    for x in 0..4 {
+        assert_eq!(unchecked(x), x * x);
+    }
+    assert_eq!(UNSAFE_SELECTS.load(Ordering::Relaxed), 1);
+}
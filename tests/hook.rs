@@ -0,0 +1,90 @@
+//! Tests for installing and uninstalling `RoHook`s.
+
+use ro_cell::{RoFn, RoHook};
+
+type Hook = fn(u32) -> u32;
+
+fn base(x: u32) -> u32 {
+    x
+}
+
+#[test]
+fn stacked_hooks_chain() {
+    static HOOK: RoHook<Hook> = unsafe { RoHook::new(base) };
+    static PREV_ADD: RoFn<Hook> = unsafe { RoFn::new(base) };
+    static PREV_DOUBLE: RoFn<Hook> = unsafe { RoFn::new(base) };
+
+    fn add(x: u32) -> u32 {
+        PREV_ADD.get()(x) + 1
+    }
+
+    fn double(x: u32) -> u32 {
+        PREV_DOUBLE.get()(x) * 2
+    }
+
+    let add_token = HOOK.install(add, &PREV_ADD);
+    assert_eq!(HOOK.get()(3), 4);
+    let double_token = HOOK.install(double, &PREV_DOUBLE);
+    assert_eq!(HOOK.get()(3), 8);
+
+    assert!(HOOK.uninstall(double_token, &PREV_DOUBLE).is_ok());
+    assert_eq!(HOOK.get()(3), 4);
+    assert!(HOOK.uninstall(add_token, &PREV_ADD).is_ok());
+    assert_eq!(HOOK.get()(3), 3);
+}
+
+#[test]
+fn out_of_order_uninstall_is_rejected() {
+    static HOOK: RoHook<Hook> = unsafe { RoHook::new(base) };
+    static PREV_ADD: RoFn<Hook> = unsafe { RoFn::new(base) };
+    static PREV_DOUBLE: RoFn<Hook> = unsafe { RoFn::new(base) };
+
+    fn add(x: u32) -> u32 {
+        PREV_ADD.get()(x) + 1
+    }
+
+    fn double(x: u32) -> u32 {
+        PREV_DOUBLE.get()(x) * 2
+    }
+
+    let add_token = HOOK.install(add, &PREV_ADD);
+    let double_token = HOOK.install(double, &PREV_DOUBLE);
+
+    // `add` is below `double`, so it cannot be removed yet.
+    let add_token = HOOK.uninstall(add_token, &PREV_ADD).unwrap_err();
+    assert_eq!(HOOK.get()(3), 8);
+
+    assert!(HOOK.uninstall(double_token, &PREV_DOUBLE).is_ok());
+    assert!(HOOK.uninstall(add_token, &PREV_ADD).is_ok());
+    assert_eq!(HOOK.get()(3), 3);
+}
+
+#[test]
+fn foreign_token_is_rejected() {
+    static FIRST: RoHook<Hook> = unsafe { RoHook::new(base) };
+    static SECOND: RoHook<Hook> = unsafe { RoHook::new(base) };
+    static PREV_FIRST: RoFn<Hook> = unsafe { RoFn::new(base) };
+    static PREV_SECOND: RoFn<Hook> = unsafe { RoFn::new(base) };
+
+    fn first(x: u32) -> u32 {
+        PREV_FIRST.get()(x) + 1
+    }
+
+    fn second(x: u32) -> u32 {
+        PREV_SECOND.get()(x) * 2
+    }
+
+    let first_token = FIRST.install(first, &PREV_FIRST);
+    let second_token = SECOND.install(second, &PREV_SECOND);
+
+    // Both hooks are the first installed on their `RoHook`, but the tokens are still told apart.
+    let first_token = SECOND.uninstall(first_token, &PREV_FIRST).unwrap_err();
+    let second_token = FIRST.uninstall(second_token, &PREV_SECOND).unwrap_err();
+    assert_eq!(FIRST.get()(3), 4);
+    assert_eq!(SECOND.get()(3), 6);
+
+    assert!(FIRST.uninstall(first_token, &PREV_FIRST).is_ok());
+    assert!(SECOND.uninstall(second_token, &PREV_SECOND).is_ok());
+    assert_eq!(FIRST.get()(3), 3);
+    assert_eq!(SECOND.get()(3), 3);
+}
@@ -0,0 +1,49 @@
+//! Tests for `ro_init!` misuse, which aborts the process before `main`.
+
+#![cfg(not(any(loom, target_vendor = "apple")))]
+
+use std::process::Command;
+
+/// Run one of the programs in `tests/init` and return its stderr. The program must fail.
+///
+/// The programs are built in release mode, where `RoCell` does no checks of its own.
+fn run(example: &str) -> String {
+    let output = Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--release", "--example", example])
+        .arg("--target-dir")
+        .arg(env!("CARGO_TARGET_TMPDIR"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        !output.status.success(),
+        "{} succeeded:\n{}",
+        example,
+        stderr
+    );
+    assert!(output.stdout.is_empty(), "{} reached main", example);
+    stderr
+}
+
+#[test]
+fn undeclared_dependency_panics() {
+    let stderr = run("init_undeclared");
+    assert!(
+        stderr.contains("ro_init! static read before its initializer ran"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn cyclic_dependency_panics() {
+    let stderr = run("init_cycle");
+    assert!(
+        stderr.contains(
+            "cyclic dependency between ro_init! initializers `init_cycle::FIRST` and `init_cycle::SECOND`"
+        ),
+        "{}",
+        stderr
+    );
+}
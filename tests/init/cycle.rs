@@ -0,0 +1,13 @@
+//! Two initializers that depend on each other.
+
+// Apple targets have no `ro_init!`.
+#[cfg(not(target_vendor = "apple"))]
+ro_cell::ro_init! {
+    static FIRST: RoInit<u32> = *SECOND + 1; after SECOND;
+    static SECOND: RoInit<u32> = *FIRST + 1; after FIRST;
+}
+
+fn main() {
+    #[cfg(not(target_vendor = "apple"))]
+    println!("{}", *FIRST);
+}
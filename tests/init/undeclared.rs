@@ -0,0 +1,14 @@
+//! `FIRST` reads `SECOND` without listing it in `after`, and runs first because `SECOND` depends
+//! on it.
+
+// Apple targets have no `ro_init!`.
+#[cfg(not(target_vendor = "apple"))]
+ro_cell::ro_init! {
+    static FIRST: RoInit<u32> = *SECOND + 1;
+    static SECOND: RoInit<u32> = 1; after FIRST;
+}
+
+fn main() {
+    #[cfg(not(target_vendor = "apple"))]
+    println!("{}", *FIRST);
+}
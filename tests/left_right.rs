@@ -0,0 +1,102 @@
+//! Concurrent tests for `RoLeftRight`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use ro_cell::RoLeftRight;
+
+const WRITES: u64 = 2_000;
+
+/// A table of counters that every write increments together, so a half-applied write shows up
+/// as counters that differ.
+fn table() -> RoLeftRight<Vec<u64>> {
+    RoLeftRight::new(vec![0; 256])
+}
+
+fn increment(table: &mut Vec<u64>) {
+    for counter in table {
+        *counter += 1;
+    }
+}
+
+/// Read both copies, by flipping the active one with a no-op write in between.
+fn both_copies(table: &RoLeftRight<Vec<u64>>) -> (Vec<u64>, Vec<u64>) {
+    let first = table.read().clone();
+    table.write(|_| ());
+    let second = table.read().clone();
+    (first, second)
+}
+
+#[test]
+fn readers_never_see_partial_writes() {
+    let table = table();
+    let done = AtomicBool::new(false);
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let mut last = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let guard = table.read();
+                    let version = guard[0];
+                    assert!(guard.iter().all(|&c| c == version), "half-applied write");
+                    // Each read sees a version at least as new as the previous one.
+                    assert!(version >= last);
+                    last = version;
+                    drop(guard);
+                    // Let the writer run even on a single core, instead of making it wait for
+                    // readers that were preempted while holding a guard.
+                    thread::yield_now();
+                }
+            });
+        }
+
+        for _ in 0..WRITES {
+            table.write(increment);
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    let (first, second) = both_copies(&table);
+    assert_eq!(first, second);
+    assert!(first.iter().all(|&c| c == WRITES));
+}
+
+#[test]
+fn writer_waits_for_readers() {
+    let table = table();
+    let guard = table.read();
+
+    thread::scope(|s| {
+        let writer = s.spawn(|| table.write(increment));
+        thread::sleep(Duration::from_millis(50));
+        // The writer may update the inactive copy, but not the one being read.
+        assert!(guard.iter().all(|&c| c == 0));
+        assert!(!writer.is_finished());
+        drop(guard);
+    });
+
+    let (first, second) = both_copies(&table);
+    assert_eq!(first, second);
+    assert!(first.iter().all(|&c| c == 1));
+}
+
+#[test]
+fn concurrent_writers_are_serialized() {
+    let table = table();
+
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..WRITES / 4 {
+                    table.write(increment);
+                }
+            });
+        }
+    });
+
+    let (first, second) = both_copies(&table);
+    assert_eq!(first, second);
+    assert!(first.iter().all(|&c| c == WRITES));
+}
@@ -0,0 +1,95 @@
+//! Model tests for the "init then publish" pattern.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --release --test loom`.
+
+#![cfg(loom)]
+
+use loom::sync::atomic::{AtomicBool, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+use ro_cell::RoCell;
+
+#[test]
+fn init_then_publish() {
+    loom::model(|| {
+        let cell = Arc::new(unsafe { RoCell::<usize>::new_uninit() });
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let reader = {
+            let cell = cell.clone();
+            let ready = ready.clone();
+            thread::spawn(move || {
+                if ready.load(Ordering::Acquire) {
+                    assert_eq!(**cell, 1);
+                }
+            })
+        };
+
+        unsafe { RoCell::init(&cell, 1) };
+        ready.store(true, Ordering::Release);
+        reader.join().unwrap();
+    });
+}
+
+#[test]
+fn replace_then_publish() {
+    loom::model(|| {
+        let cell = Arc::new(RoCell::new(1usize));
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let reader = {
+            let cell = cell.clone();
+            let ready = ready.clone();
+            thread::spawn(move || {
+                if ready.load(Ordering::Acquire) {
+                    assert_eq!(**cell, 2);
+                }
+            })
+        };
+
+        assert_eq!(unsafe { RoCell::replace(&cell, 2) }, 1);
+        ready.store(true, Ordering::Release);
+        reader.join().unwrap();
+    });
+}
+
+#[test]
+fn init_then_spawn() {
+    loom::model(|| {
+        let cell = Arc::new(unsafe { RoCell::<usize>::new_uninit() });
+        unsafe { RoCell::init(&cell, 1) };
+
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let cell = cell.clone();
+                thread::spawn(move || assert_eq!(**cell, 1))
+            })
+            .collect();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "Causality violation: Concurrent read and write accesses.")]
+fn relaxed_publish_is_a_race() {
+    loom::model(|| {
+        let cell = Arc::new(RoCell::new(1usize));
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let reader = {
+            let cell = cell.clone();
+            let ready = ready.clone();
+            thread::spawn(move || {
+                if ready.load(Ordering::Relaxed) {
+                    let _ = **cell;
+                }
+            })
+        };
+
+        unsafe { RoCell::replace(&cell, 2) };
+        ready.store(true, Ordering::Relaxed);
+        reader.join().unwrap();
+    });
+}
@@ -0,0 +1,101 @@
+//! Tests for when `RoRcu` drops replaced values.
+
+#![cfg(feature = "alloc")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use ro_cell::RoRcu;
+
+/// A value that counts how often values of its kind have been dropped.
+struct Counted<'a>(usize, &'a AtomicUsize);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn replace_waits_for_readers() {
+    let drops = AtomicUsize::new(0);
+    let rcu = RoRcu::new(Counted(0, &drops));
+    let guard = rcu.read();
+
+    thread::scope(|s| {
+        let writer = s.spawn(|| rcu.replace(Counted(1, &drops)));
+        thread::sleep(Duration::from_millis(50));
+        // The old value must stay alive while it is being read.
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        assert_eq!(guard.0, 0);
+        assert!(!writer.is_finished());
+        drop(guard);
+    });
+
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(rcu.read().0, 1);
+    drop(rcu);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn synchronize_frees_deferred_values() {
+    let drops = AtomicUsize::new(0);
+    let rcu = RoRcu::new(Counted(0, &drops));
+
+    for i in 1..=3 {
+        rcu.replace_deferred(Counted(i, &drops));
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    assert_eq!(rcu.read().0, 3);
+
+    rcu.synchronize();
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+
+    rcu.synchronize();
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+    drop(rcu);
+    assert_eq!(drops.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn drop_frees_deferred_values() {
+    let drops = AtomicUsize::new(0);
+    let rcu = RoRcu::new(Counted(0, &drops));
+
+    rcu.replace_deferred(Counted(1, &drops));
+    rcu.replace_deferred(Counted(2, &drops));
+    drop(rcu);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn concurrent_readers_and_writers() {
+    let drops = AtomicUsize::new(0);
+    let rcu = RoRcu::new(Counted(0, &drops));
+
+    thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| {
+                for _ in 0..1_000 {
+                    let guard = rcu.read();
+                    // A use after free is only caught reliably under Miri or AddressSanitizer.
+                    assert!(guard.0 <= 1_000);
+                }
+            });
+        }
+        s.spawn(|| {
+            for i in 1..=1_000 {
+                if i % 10 == 0 {
+                    rcu.replace(Counted(i, &drops));
+                } else {
+                    rcu.replace_deferred(Counted(i, &drops));
+                }
+            }
+        });
+    });
+
+    rcu.synchronize();
+    assert_eq!(drops.load(Ordering::Relaxed), 1_000);
+}
@@ -0,0 +1,85 @@
+//! Tests for `RoRegion`.
+
+#![cfg(all(feature = "std", target_os = "linux"))]
+
+use std::env;
+use std::os::unix::process::ExitStatusExt;
+use std::process::Command;
+
+use ro_cell::{RoCell, RoRegion};
+
+/// Set in the child process of `write_after_protect_faults`.
+const FAULT_CHILD: &str = "RO_REGION_FAULT_CHILD";
+
+#[test]
+fn read_after_protect() {
+    let region = RoRegion::new(64).unwrap();
+    let cell = region.alloc(1u32);
+    let uninit = unsafe { region.alloc_uninit::<u32>() };
+    unsafe { RoCell::init(uninit, 2) };
+
+    assert!(!region.is_protected());
+    region.protect().unwrap();
+    assert!(region.is_protected());
+    // Protecting twice is fine.
+    region.protect().unwrap();
+
+    assert_eq!(**cell, 1);
+    assert_eq!(**uninit, 2);
+    // Borrow guards must not write to the protected cell, not even in checked mode.
+    let borrow = RoCell::borrow(cell);
+    let again = RoCell::borrow(cell);
+    assert_eq!(*borrow + *again, 2);
+}
+
+#[test]
+#[should_panic(expected = "RoRegion is already protected")]
+fn alloc_after_protect() {
+    let region = RoRegion::new(64).unwrap();
+    region.protect().unwrap();
+    region.alloc(1u32);
+}
+
+#[test]
+#[should_panic(expected = "RoRegion is full")]
+fn alloc_when_full() {
+    let region = RoRegion::new(1).unwrap();
+    loop {
+        region.alloc([0u8; 1024]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "RoRegion cannot hold types aligned to more than a page")]
+fn alloc_over_aligned() {
+    // Larger than the usual page sizes of 4, 16 and 64 KiB.
+    #[repr(align(131072))]
+    struct Huge(#[allow(dead_code)] u8);
+
+    let region = RoRegion::new(64).unwrap();
+    unsafe { region.alloc_uninit::<Huge>() };
+}
+
+#[test]
+fn write_after_protect_faults() {
+    if env::var_os(FAULT_CHILD).is_some() {
+        let region = RoRegion::new(64).unwrap();
+        let cell = region.alloc(1u32);
+        region.protect().unwrap();
+        unsafe { *RoCell::as_mut(cell) = 2 };
+        unreachable!("write to a protected RoRegion did not fault");
+    }
+
+    // Fault in a child process running only this test.
+    let status = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "write_after_protect_faults", "--test-threads=1"])
+        .env(FAULT_CHILD, "1")
+        .status()
+        .unwrap();
+    assert_eq!(
+        status.signal(),
+        Some(11),
+        "expected SIGSEGV, got {}",
+        status
+    );
+}
@@ -0,0 +1,44 @@
+//! Tests for `ro_uninit!` and `verify_all_initialized`.
+
+#![cfg(all(feature = "registry", not(loom)))]
+
+use std::panic;
+
+use ro_cell::{ro_uninit, RoCell};
+
+ro_uninit! {
+    static PORT: RoCell<u16>;
+    static HOST: RoCell<&'static str>;
+}
+
+fn uninitialized() -> Vec<(&'static str, &'static str)> {
+    let mut names: Vec<_> = ro_cell::uninitialized()
+        .map(|reg| (reg.name(), reg.type_name()))
+        .collect();
+    names.sort();
+    names
+}
+
+// The registry is process-wide, so this is a single test.
+#[test]
+fn verify_reports_missed_cells() {
+    assert_eq!(
+        uninitialized(),
+        [("registry::HOST", "&str"), ("registry::PORT", "u16")]
+    );
+
+    unsafe { RoCell::init(&HOST, "localhost") };
+    assert_eq!(uninitialized(), [("registry::PORT", "u16")]);
+
+    let err = panic::catch_unwind(ro_cell::verify_all_initialized).unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        msg,
+        "uninitialized RoCells:\n    registry::PORT: RoCell<u16>"
+    );
+
+    unsafe { RoCell::init(&PORT, 8080) };
+    assert!(uninitialized().is_empty());
+    ro_cell::verify_all_initialized();
+    assert_eq!((*HOST, *PORT), ("localhost", 8080));
+}
@@ -0,0 +1,23 @@
+//! Tests for `seal`. The seal is process-wide, so these live in their own test binary.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use ro_cell::{RoCell, RoDyn};
+
+#[test]
+fn mutation_after_seal_panics() {
+    let cell = unsafe { RoCell::<u32>::new_uninit() };
+    let slot = RoDyn::<str>::new("default");
+    assert!(!ro_cell::is_sealed());
+    ro_cell::seal();
+    assert!(ro_cell::is_sealed());
+
+    let err = panic::catch_unwind(AssertUnwindSafe(|| unsafe { RoCell::init(&cell, 1) }));
+    assert!(err.is_err());
+    let err = panic::catch_unwind(AssertUnwindSafe(|| slot.try_set("set")));
+    assert!(err.is_err());
+    // The failed attempt leaves the slot unset rather than half-set.
+    assert!(!slot.is_set());
+    assert_eq!(slot.get(), "default");
+    std::mem::forget(cell);
+}
@@ -0,0 +1,81 @@
+//! Stress tests for `RoSeq`.
+
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use ro_cell::{NoUninit, RoSeq};
+
+const WRITES: usize = 100_000;
+
+/// Write `a` and `b` alternately while readers check that every snapshot is one of them.
+fn alternate<T: NoUninit + PartialEq + std::fmt::Debug + Send + Sync>(a: T, b: T) {
+    let seq = RoSeq::new(a);
+    let done = AtomicBool::new(false);
+
+    thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    let value = seq.read();
+                    assert!(value == a || value == b, "torn read: {:?}", value);
+                }
+            });
+        }
+
+        for i in 0..WRITES {
+            seq.write(if i % 2 == 0 { b } else { a });
+        }
+        done.store(true, Ordering::Relaxed);
+    });
+
+    assert_eq!(seq.into_inner(), a);
+}
+
+#[test]
+fn word_copy_is_not_torn() {
+    alternate([0usize; 4], [usize::MAX; 4]);
+}
+
+#[test]
+fn byte_copy_is_not_torn() {
+    // A byte-wise tear of these would not even be a valid `char`.
+    alternate(['\u{10FFFF}'; 3], ['\u{D7FF}'; 3]);
+}
+
+#[test]
+fn concurrent_writers_are_serialized() {
+    let seq = RoSeq::new([0u32; 4]);
+
+    thread::scope(|s| {
+        for i in 1..=4 {
+            let seq = &seq;
+            s.spawn(move || {
+                for _ in 0..WRITES / 4 {
+                    seq.write([i; 4]);
+                    let value = seq.read();
+                    assert!(
+                        value.iter().all(|&x| x == value[0]),
+                        "torn read: {:?}",
+                        value
+                    );
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn static_pointers() {
+    static TARGETS: [u32; 2] = [1, 2];
+    static POINTERS: RoSeq<[*const u32; 2]> = RoSeq::new([ptr::null(); 2]);
+
+    POINTERS.write([&TARGETS[0], &TARGETS[1]]);
+    let sum = thread::spawn(|| {
+        let [a, b] = POINTERS.read();
+        unsafe { *a + *b }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(sum, 3);
+}
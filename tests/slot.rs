@@ -0,0 +1,66 @@
+//! Tests for `RoSlot`. Its initialization flag exists in all builds, so these hold in release
+//! builds as well.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ro_cell::RoSlot;
+
+/// A value that counts how often values of its kind have been dropped.
+struct Counted<'a>(usize, &'a AtomicUsize);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn drop_uninit_drops_nothing() {
+    let drops = AtomicUsize::new(0);
+    let slot = unsafe { RoSlot::<Counted<'_>>::new_uninit() };
+    assert!(!RoSlot::is_initialized(&slot));
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn drop_after_init() {
+    let drops = AtomicUsize::new(0);
+    let slot = unsafe { RoSlot::new_uninit() };
+    unsafe { RoSlot::init(&slot, Counted(1, &drops)) };
+    assert!(RoSlot::is_initialized(&slot));
+    assert_eq!(slot.0, 1);
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn reinit_drops_old_value_once() {
+    let drops = AtomicUsize::new(0);
+    let slot = RoSlot::new(Counted(1, &drops));
+    unsafe { RoSlot::init(&slot, Counted(2, &drops)) };
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(slot.0, 2);
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn replace_returns_old_value() {
+    let drops = AtomicUsize::new(0);
+    let slot = RoSlot::new(Counted(1, &drops));
+    let old = unsafe { RoSlot::replace(&slot, Counted(2, &drops)) };
+    assert_eq!(old.0, 1);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(old);
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn debug() {
+    let slot = unsafe { RoSlot::new_uninit() };
+    assert_eq!(format!("{:?}", slot), "<uninit>");
+    unsafe { RoSlot::init(&slot, 42) };
+    assert_eq!(format!("{:?}", slot), "42");
+}